@@ -0,0 +1,146 @@
+//! Reference encoding plugin.
+//!
+//! Generates Python 3 programs printing the test input as hex. The template
+//! is put in front of the generated code and must define `solve()`, which
+//! runs on ignored tests without a recorded answer.
+extern crate base64;
+extern crate serde_json;
+
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+
+fn field<'a>(request: &'a Value, name: &str) -> Result<&'a Value, String> {
+    request
+        .get(name)
+        .ok_or_else(|| format!("Missing field {}", name))
+}
+fn number_field(request: &Value, name: &str) -> Result<u64, String> {
+    field(request, name)?
+        .as_u64()
+        .ok_or_else(|| format!("Field {} isn't a number", name))
+}
+fn str_field<'a>(request: &'a Value, name: &str) -> Result<&'a str, String> {
+    field(request, name)?
+        .as_str()
+        .ok_or_else(|| format!("Field {} isn't a string", name))
+}
+
+fn literal(value: &str) -> String {
+    // JSON string literals are valid Python string literals.
+    Value::from(value).to_string()
+}
+fn program(request: &Value, body: &str) -> Result<Value, String> {
+    let template = str_field(field(request, "template")?, "content")?;
+    let mut answer = String::new();
+    for i in field(request, "ignore")?
+        .as_array()
+        .ok_or("Field ignore isn't a list")?
+    {
+        answer.push_str(&format!(
+            "    {}: {},\n",
+            literal(str_field(i, "hash")?),
+            match i.get("answer").and_then(Value::as_str) {
+                Some(v) => literal(v),
+                None => "None".to_string(),
+            }
+        ));
+    }
+    Ok(json!({
+        "source": format!(
+            r#"# id: {}
+{}
+import base64
+import hashlib
+import sys
+
+answer = {{
+{}}}
+
+
+def main():
+    raw = sys.stdin.buffer.read()
+    digest = base64.b64encode(hashlib.sha256(raw).digest()).decode()
+    if digest in answer:
+        if answer[digest] is not None:
+            sys.stdout.write(answer[digest])
+        else:
+            solve()
+        return
+{}
+
+main()
+"#,
+            number_field(request, "random")?,
+            template,
+            answer,
+            body
+        )
+    }))
+}
+
+fn decode_meta(request: &Value) -> Result<Value, String> {
+    let output = str_field(field(request, "verdict")?, "output")?;
+    let mut split = output.split_whitespace();
+    let mut next = |name: &str| split.next().ok_or_else(|| format!("Can't find {}", name));
+    let size: usize = next("size")?.parse().map_err(|e| format!("{}", e))?;
+    Ok(json!({
+        "size": size,
+        "output_size": size * 2,
+        "compress_size": size,
+        "hash": next("hash")?,
+    }))
+}
+fn decode(request: &Value) -> Result<Value, String> {
+    let mut hex = String::new();
+    for i in field(request, "messages")?
+        .as_array()
+        .ok_or("Field messages isn't a list")?
+    {
+        hex.push_str(i.as_str().ok_or("Message isn't a string")?.trim());
+    }
+    let data = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|v| u8::from_str_radix(v, 16).ok())
+                .ok_or_else(|| format!("Invalid hex at {}", i))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    Ok(json!({ "data": base64::encode(data) }))
+}
+
+fn handle(request: &Value) -> Result<Value, String> {
+    match str_field(request, "method")? {
+        "meta" => program(request, "    print(len(raw))\n    print(digest)"),
+        "decode_meta" => decode_meta(request),
+        "data" => program(
+            request,
+            &format!(
+                "    sys.stdout.write(raw.hex()[{}:{}])",
+                number_field(request, "offset")?,
+                number_field(request, "offset")? + number_field(request, "length")?
+            ),
+        ),
+        "decode" => decode(request),
+        "calibrate" => Ok(json!({
+            "source": format!(
+                "# id: {}\nimport sys\n\nsys.stdout.write(\"x\" * {})\n",
+                number_field(request, "random")?,
+                number_field(request, "length")?
+            )
+        })),
+        method => Err(format!("Unknown method {}", method)),
+    }
+}
+
+fn main() {
+    let mut input = String::new();
+    let reply = io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::from_str(&input).map_err(|e| e.to_string()))
+        .and_then(|request| handle(&request))
+        .unwrap_or_else(|e| json!({ "error": e }));
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{}", reply).unwrap();
+}
@@ -0,0 +1,126 @@
+extern crate termcolor;
+
+use crate::{read::read_line, write::write_result};
+use cf_downloader::submitter::{budget::Budget, status::Event, Submitter};
+use std::{fs::File, io::Write, path::Path};
+use termcolor::{Color, StandardStream};
+
+#[allow(unused_must_use)]
+pub fn load_budget(stdout: &mut StandardStream, submitter: &Submitter, path: &Path) {
+    let budget = match File::open(path)
+        .map_err(|e| e.to_string())
+        .and_then(|f| Budget::from_reader(f).map_err(|e| e.to_string()))
+    {
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(
+                stdout,
+                "Error",
+                "Error load budget {}: {}",
+                path.display(),
+                e
+            );
+            return;
+        }
+    };
+    write_result(
+        stdout,
+        submitter.set_budget(budget),
+        &format!("Loaded budget {}", path.display()),
+    );
+}
+
+#[allow(unused_must_use)]
+fn list(stdout: &mut StandardStream, submitter: &Submitter) {
+    let status = submitter.status();
+    if status.is_empty() {
+        write_info!(stdout, "Info", "No account");
+    }
+    for s in status {
+        if s.online {
+            write_ok!(stdout, "Online", "{}", s.handle);
+        } else {
+            write_error!(stdout, "Offline", "{}", s.handle);
+        }
+    }
+}
+#[allow(unused_must_use)]
+fn status(stdout: &mut StandardStream, submitter: &Submitter) {
+    write_info!(
+        stdout,
+        "Info",
+        "Submit delay {}s",
+        submitter.submit_delay().as_secs()
+    );
+    for s in submitter.status() {
+        match (s.next_submit, s.quarantined) {
+            (_, Some(t)) => write_error!(
+                stdout,
+                "Warning",
+                "{}: {} submissions, {} ok, {} failed, quarantined for {}s",
+                s.handle,
+                s.submitted,
+                s.success,
+                s.failure,
+                t.as_secs()
+            ),
+            (Some(t), None) => write_info!(
+                stdout,
+                if s.online { "Online" } else { "Offline" },
+                "{}: {} submissions, {} ok, {} failed, next submit in {}s",
+                s.handle,
+                s.submitted,
+                s.success,
+                s.failure,
+                t.as_secs()
+            ),
+            (None, None) => write_info!(stdout, "Offline", "{}: not logged in", s.handle),
+        };
+    }
+}
+
+#[allow(unused_must_use)]
+pub fn write_events(stdout: &mut StandardStream, submitter: &Submitter) {
+    for e in submitter.take_events() {
+        match e {
+            Event::Quarantined(handle, cooldown) => write_error!(
+                stdout,
+                "Warning",
+                "{} failed repeatedly, quarantined for {}s",
+                handle,
+                cooldown.as_secs()
+            ),
+            Event::Restored(handle) => {
+                write_info!(stdout, "Info", "{} is back in rotation", handle)
+            }
+            Event::Usage(e) => write_error!(stdout, "Error", "budget: {}", e),
+            Event::Audit(e) => write_error!(stdout, "Error", "audit: {}", e),
+        };
+    }
+}
+
+#[allow(unused_must_use)]
+pub async fn accounts(stdout: &mut StandardStream, submitter: &Submitter, command: &str) {
+    match command {
+        "list" => list(stdout, submitter),
+        "status" => status(stdout, submitter),
+        "remove" => {
+            let handle = read_line(stdout, b"Handle: ");
+            write_result(
+                stdout,
+                submitter.remove(&handle).await,
+                &format!("Removed {}", handle),
+            );
+        }
+        "relogin" => {
+            let handle = read_line(stdout, b"Handle: ");
+            write_info!(stdout, "Info", "Logging in {}...", handle);
+            write_result(
+                stdout,
+                submitter.relogin(&handle).await,
+                &format!("Logged in {}", handle),
+            );
+        }
+        unknown => write_error!(stdout, "Error", "accounts: Unknown command {}", unknown),
+    }
+}
@@ -0,0 +1,62 @@
+extern crate termcolor;
+
+use crate::read::read_line;
+use cf_downloader::submitter::{
+    audit::{self, Filter},
+    Submitter,
+};
+use std::io::Write;
+use termcolor::{Color, StandardStream};
+
+fn optional(value: &str) -> Option<&str> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+#[allow(unused_must_use)]
+pub fn history(stdout: &mut StandardStream, submitter: &Submitter) {
+    let path = match submitter.audit_log() {
+        Some(v) => v,
+        None => {
+            write_error!(stdout, "Error", "Audit log disabled");
+            return;
+        }
+    };
+    let problem = read_line(stdout, b"Problem (empty for all): ");
+    let handle = read_line(stdout, b"Handle (empty for all): ");
+    let filter = Filter {
+        problem: optional(&problem),
+        handle: optional(&handle),
+    };
+    let records = match audit::history(&path, &filter) {
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(stdout, "Error", "{}", e);
+            return;
+        }
+    };
+    for r in records.iter() {
+        write_info!(
+            stdout,
+            "Record",
+            "{} {} {} {} {} {} {} {}: {}",
+            r.time,
+            r.handle,
+            r.problem,
+            r.language,
+            r.purpose,
+            r.key,
+            r.hash.get(..8).unwrap_or(&r.hash),
+            r.submission.as_deref().unwrap_or("-"),
+            r.error
+                .as_deref()
+                .or(r.verdict.as_deref())
+                .unwrap_or("pending")
+        );
+    }
+    write_ok!(stdout, "Finish", "{} records", records.len());
+}
@@ -0,0 +1,110 @@
+extern crate termcolor;
+
+use crate::read::read_reader;
+use cf_downloader::{
+    encoding::scheme::Registry,
+    job::{self, resume_all, run_all, Job, Report},
+    judge::problem::Problem,
+    submitter::Submitter,
+};
+use std::{io::Write, sync::Arc};
+use termcolor::{Color, StandardStream};
+
+#[allow(unused_must_use)]
+fn read_jobs(stdout: &mut StandardStream) -> Option<Vec<Job>> {
+    match job::from_reader(read_reader(stdout)) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            write_error!(stdout, "Error", "Error load job list: {}", e);
+            None
+        }
+    }
+}
+
+#[allow(unused_must_use)]
+fn write_reports(
+    stdout: &mut StandardStream,
+    problems: Vec<Problem>,
+    reports: Vec<Result<Report, job::Error>>,
+) {
+    for (problem, report) in problems.iter().zip(reports) {
+        match report {
+            Ok(r) => {
+                if r.skipped != 0 {
+                    write_info!(
+                        stdout,
+                        "Info",
+                        "{}: {} tests downloaded before",
+                        problem,
+                        r.skipped
+                    );
+                }
+                let total = r.tests.len();
+                let mut success = 0;
+                for (index, val) in r.tests {
+                    match val {
+                        Ok(_) => success += 1,
+                        Err(e) => {
+                            write_error!(
+                                stdout,
+                                "Error",
+                                "{} fail get test {}: {}",
+                                problem,
+                                index,
+                                e
+                            )
+                        }
+                    }
+                }
+                write_ok!(
+                    stdout,
+                    "Finish",
+                    "{}: Got {} of {} data",
+                    problem,
+                    success,
+                    total
+                );
+                if let Some(e) = r.stopped {
+                    write_error!(stdout, "Stopped", "{}: {}", problem, e);
+                }
+            }
+            Err(e) => write_error!(stdout, "Fail", "{}: {}", problem, e),
+        }
+    }
+}
+
+#[allow(unused_must_use)]
+pub async fn batch(
+    stdout: &mut StandardStream,
+    submitter: &Arc<Submitter>,
+    registry: &Arc<Registry>,
+) {
+    if let Some(jobs) = read_jobs(stdout) {
+        write_info!(stdout, "Info", "Running {} jobs", jobs.len());
+        submitter.begin_run();
+        let problems = jobs.iter().map(Job::problem).collect();
+        write_reports(
+            stdout,
+            problems,
+            run_all(jobs, submitter.clone(), registry.clone()).await,
+        );
+    }
+}
+
+#[allow(unused_must_use)]
+pub async fn resume(
+    stdout: &mut StandardStream,
+    submitter: &Arc<Submitter>,
+    registry: &Arc<Registry>,
+) {
+    if let Some(jobs) = read_jobs(stdout) {
+        write_info!(stdout, "Info", "Resuming {} jobs", jobs.len());
+        submitter.begin_run();
+        let problems = jobs.iter().map(Job::problem).collect();
+        write_reports(
+            stdout,
+            problems,
+            resume_all(jobs, submitter.clone(), registry.clone()).await,
+        );
+    }
+}
@@ -1,73 +1,179 @@
+extern crate futures;
 extern crate termcolor;
 
 use crate::{
-    read::{read_line, read_problem, read_reader, read_template, read_usize, read_writer},
+    command::account::write_events,
+    read::{
+        read_confirm, read_line, read_problem, read_reader, read_template, read_usize, read_writer,
+    },
     write::write_result,
 };
 use cf_downloader::{
-    downloader::{data::DataResult, Downloader},
-    encoding::{
-        gzip::Decoder,
-        handlebars::{encode::Encoder, meta::Meta},
-    },
+    downloader::{plan::Plan, Downloader},
+    encoding::scheme::{Registry, Scheme},
     judge::Session,
     submitter::Submitter,
 };
-use std::{fs::File, io::Write};
+use futures::StreamExt;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    num::NonZeroUsize,
+    sync::Arc,
+};
 use termcolor::{Color, StandardStream, WriteColor};
 
 #[allow(unused_must_use)]
-async fn get_data(stdout: &mut StandardStream, downloader: &mut Downloader<'_>) {
+fn read_range(stdout: &mut StandardStream, downloader: &Downloader) -> Option<(usize, usize)> {
     if downloader.is_empty() {
         write_error!(stdout, "Error", "No metadata");
-        return;
+        return None;
     }
     let begin = read_usize(stdout, b"Begin: ", 0, downloader.len());
     let end = read_usize(stdout, b"End: ", begin + 1, downloader.len() + 1);
-    match downloader
-        .get_data::<Encoder, Decoder, _>(&read_template(stdout), begin, end)
+    Some((begin, end))
+}
+
+#[allow(unused_must_use)]
+fn select_scheme(
+    stdout: &mut StandardStream,
+    registry: &Registry,
+    downloader: &Downloader,
+    name: Option<&str>,
+) -> Option<Arc<dyn Scheme>> {
+    match registry.get(name.or_else(|| downloader.scheme())) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            write_error!(
+                stdout,
+                "Error",
+                "{}. Available: {}",
+                e,
+                registry.names().join(", ")
+            );
+            None
+        }
+    }
+}
+
+#[allow(unused_must_use)]
+fn write_plan(stdout: &mut StandardStream, plan: &Plan, verbose: bool) {
+    if verbose {
+        for t in plan.tests.iter() {
+            write_info!(
+                stdout,
+                "Test",
+                "{}: {} chunks, {} cached, {} to submit",
+                t.index,
+                t.chunks,
+                t.cached,
+                t.needed()
+            );
+        }
+    }
+    write_info!(
+        stdout,
+        "Plan",
+        "{} chunks, {} cached, {} submissions with {} accounts, about {}s",
+        plan.chunks(),
+        plan.cached(),
+        plan.submissions(),
+        plan.accounts,
+        plan.estimate.as_secs()
+    );
+}
+
+fn plan(stdout: &mut StandardStream, downloader: &Downloader) {
+    if let Some((begin, end)) = read_range(stdout, downloader) {
+        write_plan(stdout, &downloader.plan(begin, end), true);
+    }
+}
+
+#[allow(unused_must_use)]
+async fn get_data(stdout: &mut StandardStream, downloader: &mut Downloader, scheme: &dyn Scheme) {
+    let (begin, end) = match read_range(stdout, downloader) {
+        Some(v) => v,
+        None => return,
+    };
+    let plan = downloader.plan(begin, end);
+    if plan.need_confirm() {
+        write_plan(stdout, &plan, false);
+        if !read_confirm(stdout, b"Continue? [y/N] ") {
+            write_info!(stdout, "Info", "Cancelled");
+            return;
+        }
+    }
+    let mut stream = match downloader
+        .stream_to(scheme, &read_template(stdout), begin, end, |index| {
+            File::create(format!("{}.in", index)).map(BufWriter::new)
+        })
         .await
     {
-        DataResult::Build(e) => write_error!(stdout, "Fail", "{}", e),
-        DataResult::Result(v) => {
-            if v.is_empty() {
-                write_ok!(stdout, "Finish", "Got {} data", end - begin);
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(stdout, "Fail", "Error building template: {}", e);
+            return;
+        }
+    };
+    let mut written = 0;
+    while let Some((index, val)) = stream.next().await {
+        match val {
+            Ok(v) => {
+                written += 1;
+                write_ok!(stdout, "Got", "test {} ({} bytes)", index, v);
             }
-            for (index, val) in (begin..end).zip(v.into_iter()) {
-                match val {
-                    Ok(v) => {
-                        if let Err(e) = File::create(format!("{}.in", index))
-                            .and_then(|mut f: File| f.write(v.as_bytes()))
-                        {
-                            write_error!(stdout, "Fail", "write data {}: {}", index, e);
-                        }
-                    }
-                    Err(e) => {
-                        write_error!(stdout, "Error", "fail get test {}: {}", index, e);
-                    }
-                }
+            Err(e) => {
+                let _ = fs::remove_file(format!("{}.in", index));
+                write_error!(stdout, "Error", "fail get test {}: {}", index, e);
             }
         }
-    };
+    }
+    if let Some(e) = stream.budget() {
+        write_error!(stdout, "Stopped", "{}", e);
+    }
+    write_ok!(stdout, "Finish", "Got {} of {} tests", written, end - begin);
 }
 
 #[allow(unused_must_use)]
-async fn get_meta(stdout: &mut StandardStream, downloader: &mut Downloader<'_>) {
+async fn get_meta(stdout: &mut StandardStream, downloader: &mut Downloader, scheme: &dyn Scheme) {
     let cnt = read_usize(stdout, b"Until: ", 0, usize::MAX);
     let template = read_template(stdout);
     write_info!(stdout, "Info", "Loading {} more testcase's metadata", cnt);
-    if let Err(e) = downloader.get_meta::<Meta, _>(&template, cnt).await {
+    if let Err(e) = downloader.get_meta(scheme, &template, cnt).await {
         write_error!(stdout, "Fail", "{}", e.to_string());
     } else {
         write_ok!(stdout, "Success", "Successfully getted metadata");
     }
 }
 
+#[allow(unused_must_use)]
+async fn calibrate(stdout: &mut StandardStream, downloader: &mut Downloader, scheme: &dyn Scheme) {
+    let template = read_template(stdout);
+    match downloader.calibrate(scheme, &template).await {
+        Ok(v) => write_ok!(stdout, "Success", "Chunk length set to {}", v),
+        Err(e) => write_error!(stdout, "Fail", "{}", e),
+    };
+}
+
+#[allow(unused_must_use)]
+fn chunk(stdout: &mut StandardStream, downloader: &mut Downloader) {
+    write_info!(
+        stdout,
+        "Info",
+        "Current chunk length: {}",
+        downloader.chunk_length()
+    );
+    let length = read_usize(stdout, b"Chunk length: ", 1, usize::MAX);
+    downloader.set_chunk_length(NonZeroUsize::new(length).unwrap());
+    write_ok!(stdout, "Success", "Chunk length set to {}", length);
+}
+
 #[allow(unused_must_use)]
 pub async fn problem_loop(
     stdout: &mut StandardStream,
     session: &Session,
-    submitter: &'_ mut Submitter,
+    submitter: &Arc<Submitter>,
+    registry: &Registry,
 ) {
     let problem = read_problem(stdout, session).await;
     write_info!(
@@ -79,16 +185,38 @@ pub async fn problem_loop(
     );
     stdout.reset();
     let prompt = format!("cf-downloader [{} {}]> ", problem.contest, problem.id);
-    let mut downloader: Downloader = Downloader::new(problem, submitter);
+    let mut downloader: Downloader = Downloader::new(problem, submitter.clone());
     let stdout_ptr: *mut StandardStream = stdout;
     loop {
-        match read_line(stdout, prompt.as_bytes()).trim() {
-            "get_meta" => get_meta(stdout, &mut downloader).await,
+        let line = read_line(stdout, prompt.as_bytes());
+        let (command, arg) = match line.trim().split_once(' ') {
+            Some((c, a)) => (c, Some(a.trim())),
+            None => (line.trim(), None),
+        };
+        match command {
+            "get_meta" => {
+                if let Some(s) = select_scheme(stdout, registry, &downloader, arg) {
+                    get_meta(stdout, &mut downloader, &*s).await
+                }
+            }
             "unselect" => {
                 write_info!(stdout, "Info", "Unselected problem");
                 break;
             }
-            "get_data" => get_data(stdout, &mut downloader).await,
+            "plan" => plan(stdout, &downloader),
+            "calibrate" => {
+                if let Some(s) = select_scheme(stdout, registry, &downloader, arg) {
+                    submitter.begin_run();
+                    calibrate(stdout, &mut downloader, &*s).await
+                }
+            }
+            "chunk" => chunk(stdout, &mut downloader),
+            "get_data" => {
+                if let Some(s) = select_scheme(stdout, registry, &downloader, arg) {
+                    submitter.begin_run();
+                    get_data(stdout, &mut downloader, &*s).await
+                }
+            }
             "load_meta" => write_result(
                 stdout,
                 downloader.load_meta(read_reader(unsafe { &mut *stdout_ptr })),
@@ -106,12 +234,20 @@ pub async fn problem_loop(
                     .load(read_reader(unsafe { &mut *stdout_ptr })),
                 "Loaded cache from file",
             ),
+            "save_cache" => write_result(
+                stdout,
+                downloader
+                    .cache
+                    .save(read_writer(unsafe { &mut *stdout_ptr })),
+                "Written cache to file",
+            ),
             "flush_cache" => {
                 downloader.cache.flush();
                 write_ok!(stdout, "Success", "Flushed cache");
             }
             unknown => write_error!(stdout, "Error", "problem: Unknown command {}", unknown),
         }
+        write_events(stdout, submitter);
         stdout.reset();
     }
     stdout.reset();
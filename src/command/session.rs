@@ -1,34 +1,158 @@
 extern crate termcolor;
 
-use crate::read::{read_line, read_usize};
+use crate::read::{read_line, read_passphrase, read_password, read_usize};
 use cf_downloader::{
-    account::{self, Account},
-    judge::Session,
-    submitter::{self, Submitter},
+    account::{self, vault, Account},
+    judge::{session::SavedSession, Session},
+    submitter::Submitter,
 };
 use std::{
-    fs::File,
-    io::{Read, Write},
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
 };
 use termcolor::{Color, StandardStream};
 
+/// Where the sessions of the logged in accounts are kept. Sessions of vault
+/// accounts are encrypted with the vault passphrase.
+pub struct SessionFile {
+    path: PathBuf,
+    passphrase: Option<String>,
+}
+
 #[allow(unused_must_use)]
-pub async fn login<R: Read>(stdout: &mut StandardStream, submitter: &mut Submitter, rdr: R) {
-    write_info!(stdout, "Info", "Logging in...");
-    let err: Result<Vec<submitter::Error>, ()> = try {
-        submitter
-            .login(account::from_reader(rdr).map_err(|e| {
-                write_error!(stdout, "Error", "Error load account: {}", e);
-            })?)
-            .await
+fn load_sessions(stdout: &mut StandardStream, file: &SessionFile) -> Vec<SavedSession> {
+    let path = &file.path;
+    if !path.exists() {
+        return Vec::new();
+    }
+    let sessions = fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| match &file.passphrase {
+            Some(p) if vault::is_vault(&content) => {
+                vault::sessions_from_reader(content.as_slice(), p).map_err(|e| e.to_string())
+            }
+            _ => account::sessions_from_reader(content.as_slice()).map_err(|e| e.to_string()),
+        });
+    match sessions {
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(stdout, "Error", "Error load {}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+#[allow(unused_must_use)]
+pub fn save_sessions(stdout: &mut StandardStream, submitter: &Submitter, file: &SessionFile) {
+    let path = &file.path;
+    if let Err(e) =
+        File::create(path)
+            .map_err(|e| e.to_string())
+            .and_then(|f| match &file.passphrase {
+                Some(p) => {
+                    vault::sessions_to_writer(f, &submitter.save(), p).map_err(|e| e.to_string())
+                }
+                None => {
+                    account::sessions_to_writer(f, &submitter.save()).map_err(|e| e.to_string())
+                }
+            })
+    {
+        write_error!(stdout, "Error", "Error save {}: {}", path.display(), e);
+    }
+}
+
+#[allow(unused_must_use)]
+pub async fn login(
+    stdout: &mut StandardStream,
+    submitter: &Submitter,
+    path: &Path,
+) -> Option<SessionFile> {
+    let content = match fs::read(path) {
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(stdout, "Error", "Error open {}: {}", path.display(), e);
+            return None;
+        }
     };
-    if let Ok(e) = err {
-        if e.is_empty() {
-            write_ok!(stdout, "Success", "Logged into codeforces.com");
-        } else {
-            e.into_iter()
-                .for_each(|e| write_error!(stdout, "Error", "login: {}", e));
+    let (accounts, passphrase) = if vault::is_vault(&content) {
+        let passphrase = read_passphrase(stdout, false);
+        (
+            vault::from_reader(content.as_slice(), &passphrase).map_err(|e| e.to_string()),
+            Some(passphrase),
+        )
+    } else {
+        (
+            account::from_reader(content.as_slice()).map_err(|e| e.to_string()),
+            None,
+        )
+    };
+    let accounts = match accounts {
+        Ok(v) => v,
+        Err(e) => {
+            write_error!(stdout, "Error", "Error load account: {}", e);
+            return None;
+        }
+    };
+    let file = SessionFile {
+        path: account::session_path(path),
+        passphrase,
+    };
+    let saved = load_sessions(stdout, &file);
+    write_info!(stdout, "Info", "Logging in...");
+    let e = submitter.restore(accounts, saved).await;
+    if e.is_empty() {
+        write_ok!(stdout, "Success", "Logged into codeforces.com");
+    } else {
+        e.into_iter()
+            .for_each(|e| write_error!(stdout, "Error", "login: {}", e));
+    }
+    save_sessions(stdout, submitter, &file);
+    Some(file)
+}
+
+#[allow(unused_must_use)]
+pub fn add_account(stdout: &mut StandardStream) {
+    let path = read_line(stdout, b"Vault path: ");
+    let path = Path::new(&path);
+    let (passphrase, mut accounts) = if path.exists() {
+        let passphrase = read_passphrase(stdout, false);
+        match File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|f| vault::from_reader(f, &passphrase).map_err(|e| e.to_string()))
+        {
+            Ok(v) => (passphrase, v),
+            Err(e) => {
+                write_error!(stdout, "Error", "Error load {}: {}", path.display(), e);
+                return;
+            }
         }
+    } else {
+        (read_passphrase(stdout, true), Vec::new())
+    };
+    let handle = read_line(stdout, b"Handle: ");
+    let password = read_password(stdout, b"Password: ");
+    let proxy = read_line(stdout, b"Proxy (empty for none): ");
+    let account = Account {
+        handle,
+        password,
+        proxy: if proxy.is_empty() { None } else { Some(proxy) },
+    };
+    match accounts.iter_mut().find(|x| x.handle == account.handle) {
+        Some(v) => *v = account,
+        None => accounts.push(account),
+    }
+    match File::create(path)
+        .map_err(|e| e.to_string())
+        .and_then(|f| vault::to_writer(f, &accounts, &passphrase).map_err(|e| e.to_string()))
+    {
+        Ok(_) => write_ok!(
+            stdout,
+            "Success",
+            "Saved {} account to {}",
+            accounts.len(),
+            path.display()
+        ),
+        Err(e) => write_error!(stdout, "Error", "Error save {}: {}", path.display(), e),
     }
 }
 
@@ -70,7 +194,7 @@ pub async fn register(stdout: &mut StandardStream) -> Option<Vec<Session>> {
     }
 }
 #[allow(unused_must_use)]
-pub async fn logout(stdout: &mut StandardStream, submitter: &mut Submitter) {
+pub async fn logout(stdout: &mut StandardStream, submitter: &Submitter) {
     write_info!(stdout, "Info", "Logging out from codeforces.com");
     let v = submitter.logout().await;
     if v.is_empty() {
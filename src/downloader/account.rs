@@ -2,10 +2,12 @@ extern crate serde;
 extern crate serde_yaml;
 extern crate tokio;
 
+pub mod vault;
+
 use crate::{
     config::register::{HANDLE_LEN, PASSWORD_LEN, REGISTER_DELAY},
     email::{self, Email},
-    judge::{self, Session},
+    judge::{self, session::SavedSession, Session},
     random::random_hex,
 };
 use serde::{Deserialize, Serialize};
@@ -13,6 +15,7 @@ use std::{
     error::Error,
     fmt,
     io::{Read, Write},
+    path::{Path, PathBuf},
     vec::Vec,
 };
 use tokio::time::sleep;
@@ -39,7 +42,7 @@ impl Error for RegisterError {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Account {
     pub handle: String,
     pub password: String,
@@ -86,3 +89,16 @@ pub async fn register(
 pub fn to_writer<W: Write>(wdr: W, list: &[Account]) -> Result<(), serde_yaml::Error> {
     serde_yaml::to_writer(wdr, list)
 }
+
+pub fn session_path(account: &Path) -> PathBuf {
+    account.with_extension("session.yaml")
+}
+pub fn sessions_from_reader<R: Read>(rdr: R) -> Result<Vec<SavedSession>, serde_yaml::Error> {
+    serde_yaml::from_reader(rdr)
+}
+pub fn sessions_to_writer<W: Write>(
+    wdr: W,
+    list: &[SavedSession],
+) -> Result<(), serde_yaml::Error> {
+    serde_yaml::to_writer(wdr, list)
+}
@@ -0,0 +1,156 @@
+extern crate base64;
+extern crate openssl;
+extern crate serde;
+extern crate serde_yaml;
+
+use super::Account;
+use crate::{
+    config::vault::{ITERATIONS, KEY_LEN, MAX_ITERATIONS, NONCE_LEN, SALT_LEN, TAG_LEN},
+    judge::session::SavedSession,
+};
+use openssl::{
+    error::ErrorStack,
+    hash::MessageDigest,
+    pkcs5::pbkdf2_hmac,
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    error::Error as StdError,
+    fmt,
+    io::{Read, Write},
+};
+
+pub const PASSPHRASE_ENV: &str = "CF_DOWNLOADER_PASSPHRASE";
+const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum Error {
+    Yaml(serde_yaml::Error),
+    Base64(base64::DecodeError),
+    Crypto(ErrorStack),
+    Version(u32),
+    Iterations(u32),
+    Decrypt,
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Yaml(e) => write!(f, "Error process vault: {}", e),
+            Self::Base64(e) => write!(f, "Error decode vault: {}", e),
+            Self::Crypto(e) => write!(f, "Error encrypt vault: {}", e),
+            Self::Version(v) => write!(f, "Unsupported vault version {}", v),
+            Self::Iterations(v) => write!(f, "Unsupported vault iteration count {}", v),
+            Self::Decrypt => f.write_str("Wrong passphrase or corrupted vault"),
+        }
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Yaml(e) => Some(e),
+            Self::Base64(e) => Some(e),
+            Self::Crypto(e) => Some(e),
+            Self::Version(_) | Self::Iterations(_) | Self::Decrypt => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Vault {
+    vault: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    tag: String,
+    data: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<Vec<u8>, Error> {
+    let mut key = vec![0; KEY_LEN];
+    pbkdf2_hmac(
+        passphrase.as_bytes(),
+        salt,
+        iterations as usize,
+        MessageDigest::sha256(),
+        &mut key,
+    )
+    .map_err(Error::Crypto)?;
+    Ok(key)
+}
+fn decode(text: &str) -> Result<Vec<u8>, Error> {
+    base64::decode(text).map_err(Error::Base64)
+}
+
+pub fn is_vault(content: &[u8]) -> bool {
+    serde_yaml::from_slice::<Vault>(content).is_ok()
+}
+fn open<R: Read, T: DeserializeOwned>(rdr: R, passphrase: &str) -> Result<T, Error> {
+    let vault: Vault = serde_yaml::from_reader(rdr).map_err(Error::Yaml)?;
+    if vault.vault != VERSION {
+        return Err(Error::Version(vault.vault));
+    }
+    // Fewer iterations make a weak key, too many hang the login.
+    if !(ITERATIONS..=MAX_ITERATIONS).contains(&vault.iterations) {
+        return Err(Error::Iterations(vault.iterations));
+    }
+    let key = derive_key(passphrase, &decode(&vault.salt)?, vault.iterations)?;
+    let plain = decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&decode(&vault.nonce)?),
+        &[],
+        &decode(&vault.data)?,
+        &decode(&vault.tag)?,
+    )
+    .map_err(|_| Error::Decrypt)?;
+    serde_yaml::from_slice(&plain).map_err(Error::Yaml)
+}
+fn seal<W: Write, T: Serialize + ?Sized>(wdr: W, value: &T, passphrase: &str) -> Result<(), Error> {
+    let mut salt = vec![0; SALT_LEN];
+    let mut nonce = vec![0; NONCE_LEN];
+    let mut tag = vec![0; TAG_LEN];
+    rand_bytes(&mut salt).map_err(Error::Crypto)?;
+    rand_bytes(&mut nonce).map_err(Error::Crypto)?;
+    let key = derive_key(passphrase, &salt, ITERATIONS)?;
+    let data = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&nonce),
+        &[],
+        &serde_yaml::to_vec(value).map_err(Error::Yaml)?,
+        &mut tag,
+    )
+    .map_err(Error::Crypto)?;
+    serde_yaml::to_writer(
+        wdr,
+        &Vault {
+            vault: VERSION,
+            iterations: ITERATIONS,
+            salt: base64::encode(salt),
+            nonce: base64::encode(nonce),
+            tag: base64::encode(tag),
+            data: base64::encode(data),
+        },
+    )
+    .map_err(Error::Yaml)
+}
+
+pub fn from_reader<R: Read>(rdr: R, passphrase: &str) -> Result<Vec<Account>, Error> {
+    open(rdr, passphrase)
+}
+pub fn to_writer<W: Write>(wdr: W, list: &[Account], passphrase: &str) -> Result<(), Error> {
+    seal(wdr, list, passphrase)
+}
+/// Sessions of vault accounts are kept in a vault with the same passphrase.
+pub fn sessions_from_reader<R: Read>(rdr: R, passphrase: &str) -> Result<Vec<SavedSession>, Error> {
+    open(rdr, passphrase)
+}
+pub fn sessions_to_writer<W: Write>(
+    wdr: W,
+    list: &[SavedSession],
+    passphrase: &str,
+) -> Result<(), Error> {
+    seal(wdr, list, passphrase)
+}
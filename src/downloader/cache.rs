@@ -1,11 +1,11 @@
 extern crate serde;
 
 use crate::{
-    judge::{problem::Problem, Verdict},
+    judge::{problem::Problem, submit::Submission, Verdict},
     submitter::Submitter,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt, rc::Rc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SubmitKey {
@@ -18,22 +18,31 @@ impl fmt::Display for SubmitKey {
     }
 }
 
-pub struct Cache<'a> {
-    problem: Rc<Problem>,
-    pub(crate) submitter: &'a mut Submitter,
+pub struct Cache {
+    problem: Arc<Problem>,
+    pub(crate) submitter: Arc<Submitter>,
     cache: HashMap<SubmitKey, Verdict>,
+    pending: HashMap<SubmitKey, Submission>,
 }
 
-impl<'a> Cache<'a> {
-    pub fn new(problem: Rc<Problem>, submitter: &'a mut Submitter) -> Self {
+impl Cache {
+    pub fn new(problem: Arc<Problem>, submitter: Arc<Submitter>) -> Self {
         Self {
             problem,
             submitter,
             cache: HashMap::new(),
+            pending: HashMap::new(),
         }
     }
+    pub fn contains(&self, key: &SubmitKey) -> bool {
+        self.cache.contains_key(key) || self.pending.contains_key(key)
+    }
+    pub(crate) fn remove(&mut self, key: &SubmitKey) {
+        self.cache.remove(key);
+    }
     pub fn flush(&mut self) {
         self.cache.clear();
+        self.pending.clear();
     }
 }
 
@@ -2,7 +2,7 @@ extern crate serde;
 extern crate serde_yaml;
 
 use super::{Cache, SubmitKey};
-use crate::judge::{problem::Problem, Verdict};
+use crate::judge::{problem::Problem, submit::Submission, Verdict};
 use serde::{Deserialize, Serialize};
 use serde_yaml::{from_reader, to_writer};
 use std::{
@@ -40,20 +40,24 @@ impl StdError for StoageError {
 struct SaveContent<'a> {
     problem: &'a Problem,
     content: &'a HashMap<SubmitKey, Verdict>,
+    pending: &'a HashMap<SubmitKey, Submission>,
 }
 #[derive(Deserialize)]
 struct LoadContent {
     problem: Problem,
     content: HashMap<SubmitKey, Verdict>,
+    #[serde(default)]
+    pending: HashMap<SubmitKey, Submission>,
 }
 
-impl<'a> Cache<'a> {
+impl Cache {
     pub fn save<W: Write>(&self, wr: W) -> Result<(), StoageError> {
         to_writer(
             wr,
             &SaveContent {
                 problem: &self.problem,
                 content: &self.cache,
+                pending: &self.pending,
             },
         )
         .map_err(StoageError::Yaml)
@@ -67,6 +71,7 @@ impl<'a> Cache<'a> {
             ))
         } else {
             self.cache = val.content;
+            self.pending = val.pending;
             Ok(())
         }
     }
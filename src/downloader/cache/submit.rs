@@ -3,12 +3,16 @@ extern crate futures;
 use super::{Cache, SubmitKey};
 use crate::{
     error::Error as ErrType,
-    judge::{self, submit::Submission, Verdict},
-    submitter,
+    judge::{
+        self,
+        submit::{Rejection, Submission},
+        Verdict,
+    },
+    submitter::{self, budget::Exceeded},
 };
 use futures::future::join_all;
 use std::{
-    collections::HashMap, error::Error as StdError, fmt, iter::IntoIterator, mem::MaybeUninit,
+    error::Error as StdError, fmt, iter::IntoIterator, mem::MaybeUninit,
     result::Result as StdResult,
 };
 
@@ -56,8 +60,48 @@ pub struct Handle<E: 'static + ErrType> {
     id: SubmitKey,
     state: State<E>,
 }
+impl<E: 'static + ErrType> Handle<E> {
+    pub fn key(&self) -> SubmitKey {
+        self.id
+    }
+    pub fn submission(&self) -> Option<&Submission> {
+        match &self.state {
+            State::Miss(s) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn rejection(&self) -> Option<Rejection> {
+        match &self.state {
+            State::Error(Kind::Submit(e)) => e.rejection(),
+            _ => None,
+        }
+    }
+    pub fn budget(&self) -> Option<Exceeded> {
+        match &self.state {
+            State::Error(Kind::Submit(e)) => e.budget(),
+            _ => None,
+        }
+    }
+    /// Wait for the judge without touching the cache, the verdict is stored
+    /// by `Cache::record`. The chunk is printed on test `id.test`, so that is
+    /// the test whose verdict is read, not the chunk offset `id.time`.
+    pub(crate) async fn judge(self, length: usize) -> Judged<E> {
+        let verdict = match &self.state {
+            State::Miss(s) => Some(s.wait(self.id.test, length).await),
+            _ => None,
+        };
+        Judged {
+            handle: self,
+            verdict,
+        }
+    }
+}
+pub(crate) struct Judged<E: 'static + ErrType> {
+    handle: Handle<E>,
+    verdict: Option<StdResult<Verdict, judge::Error>>,
+}
 
-impl<'a> Cache<'a> {
+impl Cache {
     pub(crate) async fn submit_iter<Fun, Iter, Err>(
         &mut self,
         iter: Iter,
@@ -69,65 +113,119 @@ impl<'a> Cache<'a> {
         Iter: IntoIterator<Item = SubmitKey>,
         Err: ErrType + 'static,
     {
+        let keys: Vec<SubmitKey> = iter.into_iter().collect();
+        // Submissions loaded from a file are attached to their account again,
+        // the ones that can't be are forgotten and submitted anew.
+        let submitter = &self.submitter;
+        let attached = join_all(
+            keys.iter()
+                .filter_map(|id| Some((*id, self.pending.get(id)?)))
+                .filter(|(_, s)| !s.is_attached())
+                .map(|(id, s)| {
+                    let mut s = s.clone();
+                    async move { (id, submitter.attach(&mut s).await.map(|_| s)) }
+                }),
+        )
+        .await;
+        for (id, s) in attached {
+            match s {
+                Ok(s) => self.pending.insert(id, s),
+                Err(_) => self.pending.remove(&id),
+            };
+        }
+
         let mut ret: Vec<Handle<Err>> = Vec::new();
         let mut submit = Vec::new();
         let cache = &self.cache;
-        self.submitter
+        let pending = &self.pending;
+        let result = self
+            .submitter
             .submit_iter(
                 &self.problem,
                 language,
-                iter.into_iter().enumerate().filter_map(|(index, id)| {
+                keys.into_iter().enumerate().filter_map(|(index, id)| {
                     #[allow(clippy::uninit_assumed_init)]
                     ret.push(Handle {
                         id,
                         state: unsafe { MaybeUninit::uninit().assume_init() },
                     });
-                    match cache.get(&id) {
-                        Some(_) => {
-                            ret[index].state = State::Hit;
+                    if cache.contains_key(&id) {
+                        ret[index].state = State::Hit;
+                        return None;
+                    }
+                    if let Some(s) = pending.get(&id) {
+                        ret[index].state = State::Miss(s.clone());
+                        return None;
+                    }
+                    match generate(id) {
+                        Ok(v) => {
+                            submit.push(index);
+                            Some((id, v))
+                        }
+                        Err(e) => {
+                            ret[index].state = State::Error(Kind::Generate(e));
                             None
                         }
-                        None => match generate(id) {
-                            Ok(v) => {
-                                submit.push(index);
-                                Some(v)
-                            }
-                            Err(e) => {
-                                ret[index].state = State::Error(Kind::Generate(e));
-                                None
-                            }
-                        },
                     }
                 }),
             )
-            .await
-            .into_iter()
-            .zip(submit)
-            .for_each(|(v, id)| match v {
-                Ok(s) => ret[id].state = State::Miss(s),
-                Err(e) => ret[id].state = State::Error(Kind::Submit(e)),
-            });
+            .await;
+        for (v, index) in result.into_iter().zip(submit) {
+            match v {
+                Ok(s) => {
+                    self.pending.insert(ret[index].id, s.clone());
+                    ret[index].state = State::Miss(s);
+                }
+                Err(e) => ret[index].state = State::Error(Kind::Submit(e)),
+            }
+        }
         ret
     }
+    pub(crate) fn skip_iter<Iter, Fun, Err>(&self, iter: Iter, error: Fun) -> Vec<Handle<Err>>
+    where
+        Iter: IntoIterator<Item = SubmitKey>,
+        Fun: Fn() -> submitter::Error,
+        Err: ErrType + 'static,
+    {
+        iter.into_iter()
+            .map(|id| Handle {
+                id,
+                state: if self.cache.contains_key(&id) {
+                    State::Hit
+                } else {
+                    State::Error(Kind::Submit(error()))
+                },
+            })
+            .collect()
+    }
     pub(crate) async fn get_result<Err: ErrType + 'static>(
-        &'a mut self,
-        mut handles: Vec<Handle<Err>>,
-    ) -> Vec<StdResult<&'a Verdict, Error<Err>>> {
-        {
-            let cache: *mut HashMap<SubmitKey, Verdict> = &mut self.cache;
-            unsafe {
-                join_all(handles.iter_mut().map(async move |x| {
-                    if let State::Miss(s) = &x.state {
-                        match s.wait(x.id.time).await {
-                            Ok(v) => {
-                                (*cache).insert(x.id, v);
-                            }
-                            Err(e) => x.state = State::Error(Kind::GetResult(e)),
-                        }
-                    };
-                }))
+        &mut self,
+        handles: Vec<Handle<Err>>,
+        length: usize,
+    ) -> Vec<StdResult<&Verdict, Error<Err>>> {
+        let judged = join_all(handles.into_iter().map(|h| h.judge(length))).await;
+        self.record(judged)
+    }
+    pub(crate) fn record<Err: ErrType + 'static>(
+        &mut self,
+        judged: Vec<Judged<Err>>,
+    ) -> Vec<StdResult<&Verdict, Error<Err>>> {
+        let (mut handles, verdicts): (Vec<_>, Vec<_>) =
+            judged.into_iter().map(|x| (x.handle, x.verdict)).unzip();
+        for (x, v) in handles.iter_mut().zip(verdicts) {
+            if let Some(v) = &v {
+                if let (State::Miss(s), Ok(v)) = (&x.state, v) {
+                    self.submitter.audit_verdict(s, v.verdict());
+                }
+                self.pending.remove(&x.id);
+            }
+            match v {
+                Some(Ok(v)) => {
+                    self.cache.insert(x.id, v);
+                }
+                Some(Err(e)) => x.state = State::Error(Kind::GetResult(e)),
+                None => (),
             }
-            .await;
         }
         let cache = &self.cache;
         handles
@@ -2,7 +2,10 @@ pub mod retry {
     use std::time::Duration;
     pub const RETRY_COUNT: u32 = 10;
     pub const RETRY_DELAY: Duration = Duration::from_millis(200);
+    pub const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
     pub const FORBIDDEN_DELAY: Duration = Duration::from_secs(120);
+    pub const FORBIDDEN_MAX_DELAY: Duration = Duration::from_secs(600);
+    pub const RETRY_JITTER: f64 = 0.5;
 }
 pub mod judge {
     pub mod session {
@@ -13,12 +16,34 @@ pub mod judge {
         use std::time::Duration;
         pub const CHECK_DELAY: Duration = Duration::from_secs(2);
     }
+    pub mod contest {
+        pub const API_URL: &str = "https://codeforces.com/api";
+    }
 }
 pub mod submitter {
     use std::time::Duration;
     pub const DELAY_PER_ACCOUNT: Duration = Duration::from_secs(15);
     pub const SUBMISSION_GET_DELAY: Duration = Duration::from_secs(1);
     pub const SUBMIT_DELAY: Duration = Duration::from_secs(5);
+    pub const MAX_SUBMIT_DELAY: Duration = Duration::from_secs(120);
+    pub const SUBMIT_DELAY_RECOVER: Duration = Duration::from_secs(1);
+    pub const LIMIT_RETRY: usize = 3;
+    pub const QUARANTINE_THRESHOLD: usize = 3;
+    pub const QUARANTINE_COOLDOWN: Duration = Duration::from_secs(600);
+}
+pub mod encoding {
+    pub const DEFAULT_SCHEME: &str = "handlebars";
+    pub const PARTIALS_DIR: &str = "partials";
+}
+pub mod chunk {
+    pub const DEFAULT_LENGTH: usize = 500;
+    pub const PROBE_LENGTH: usize = 4096;
+    pub const TRUNCATION_MARKER: &str = "...";
+    pub const WRONG_ANSWER: &str = "Wrong answer";
+}
+pub mod downloader {
+    pub const DUPLICATE_RETRY: usize = 3;
+    pub const CONFIRM_SUBMISSIONS: usize = 100;
 }
 pub mod register {
     use std::time::Duration;
@@ -30,3 +55,11 @@ pub mod email {
     use std::time::Duration;
     pub const CHECK_DELAY: Duration = Duration::from_secs(5);
 }
+pub mod vault {
+    pub const ITERATIONS: u32 = 200_000;
+    pub const MAX_ITERATIONS: u32 = 10_000_000;
+    pub const KEY_LEN: usize = 32;
+    pub const SALT_LEN: usize = 16;
+    pub const NONCE_LEN: usize = 12;
+    pub const TAG_LEN: usize = 16;
+}
@@ -1,22 +1,32 @@
-use crate::{cache::Cache, judge::problem::Problem, submitter::Submitter, types::TestMeta};
-use std::{rc::Rc, vec::Vec};
+use crate::{
+    cache::Cache, config::chunk::DEFAULT_LENGTH, judge::problem::Problem, submitter::Submitter,
+    types::TestMeta,
+};
+use std::{num::NonZeroUsize, sync::Arc, vec::Vec};
 
+pub mod calibrate;
 pub mod data;
 pub mod meta;
 pub mod meta_storage;
+pub mod plan;
+pub mod stream;
 
-pub struct Downloader<'a> {
-    problem: Rc<Problem>,
+pub struct Downloader {
+    problem: Arc<Problem>,
     data: Vec<TestMeta>,
-    pub cache: Cache<'a>,
+    chunk: usize,
+    scheme: Option<String>,
+    pub cache: Cache,
 }
 
-impl<'a> Downloader<'a> {
-    pub fn new(problem: Problem, submitter: &'a mut Submitter) -> Self {
-        let r = Rc::from(problem);
+impl Downloader {
+    pub fn new(problem: Problem, submitter: Arc<Submitter>) -> Self {
+        let r = Arc::from(problem);
         Self {
             problem: r.clone(),
             data: Vec::new(),
+            chunk: DEFAULT_LENGTH,
+            scheme: None,
             cache: Cache::new(r, submitter),
         }
     }
@@ -26,4 +36,19 @@ impl<'a> Downloader<'a> {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+    /// Scheme the metadata was collected with.
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+    pub fn chunk_length(&self) -> usize {
+        self.chunk
+    }
+    /// Cached chunks are keyed by offset, so they are dropped when the length changes.
+    pub fn set_chunk_length(&mut self, length: NonZeroUsize) {
+        let length = length.get();
+        if self.chunk != length {
+            self.chunk = length;
+            self.cache.flush();
+        }
+    }
 }
@@ -0,0 +1,88 @@
+use super::Downloader;
+use crate::{
+    cache::SubmitKey,
+    config::chunk::{PROBE_LENGTH, WRONG_ANSWER},
+    encoding::{
+        scheme::{Error as SchemeError, Scheme},
+        Template,
+    },
+    error::Error as ErrType,
+    judge,
+    submitter::{self, audit::Purpose},
+};
+use std::{cmp::min, error::Error as StdError, fmt, num::NonZeroUsize};
+
+#[derive(Debug)]
+pub enum Error<E: ErrType + 'static> {
+    Build(E),
+    Submit(submitter::Error),
+    GetResult(judge::Error),
+    Verdict(String),
+    Empty,
+}
+impl<E: ErrType + 'static> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Build(e) => write!(f, "Error building template: {}", e),
+            Self::Submit(e) => write!(f, "Error submit calibration: {}", e),
+            Self::GetResult(e) => write!(f, "Error getting calibration result: {}", e),
+            Self::Verdict(v) => write!(f, "Calibration expected wrong answer, got {}", v),
+            Self::Empty => f.write_str("Calibration output is empty"),
+        }
+    }
+}
+impl<E: ErrType + 'static> StdError for Error<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Build(e) => Some(e),
+            Self::Submit(e) => Some(e),
+            Self::GetResult(e) => Some(e),
+            Self::Verdict(_) | Self::Empty => None,
+        }
+    }
+}
+
+impl Downloader {
+    /// Submit a program printing `PROBE_LENGTH` characters and use the
+    /// visible part of its output as chunk length. The program has to get
+    /// a wrong answer, other verdicts mean it didn't print its output.
+    pub async fn calibrate(
+        &mut self,
+        scheme: &dyn Scheme,
+        template: &Template,
+    ) -> Result<usize, Error<SchemeError>> {
+        let submission = self
+            .cache
+            .submitter
+            .submit(
+                &self.problem,
+                &template.language,
+                scheme
+                    .calibration(template, PROBE_LENGTH)
+                    .map_err(Error::Build)?
+                    .as_str(),
+                SubmitKey { test: 1, time: 0 },
+                Purpose::Calibrate,
+            )
+            .await
+            .map_err(Error::Submit)?;
+        let verdict = submission
+            .wait(1, self.chunk_length())
+            .await
+            .map_err(Error::GetResult)?;
+        self.cache
+            .submitter
+            .audit_verdict(&submission, verdict.verdict());
+        if !verdict.verdict().starts_with(WRONG_ANSWER) {
+            return Err(Error::Verdict(verdict.verdict().to_string()));
+        }
+        let length = min(
+            verdict
+                .truncated(PROBE_LENGTH)
+                .unwrap_or_else(|| verdict.output.trim_end().len()),
+            PROBE_LENGTH,
+        );
+        self.set_chunk_length(NonZeroUsize::new(length).ok_or(Error::Empty)?);
+        Ok(length)
+    }
+}
@@ -1,20 +1,32 @@
-extern crate futures;
-
 use super::Downloader;
 use crate::{
     cache::{self, submit::Handle, Cache, SubmitKey},
-    encoding::{DataDecoder, DataEncoder, Template},
+    config::downloader::DUPLICATE_RETRY,
+    encoding::{
+        scheme::{Error as SchemeError, Scheme},
+        DataDecoder, DataEncoder, Template,
+    },
     error::Error as ErrType,
-    types::BLOCK,
+    judge::{submit::Rejection, Verdict},
+    submitter::{self, budget::Exceeded},
+    types::TestMeta,
+};
+use std::{
+    cmp::min,
+    convert::Infallible,
+    error::Error as StdError,
+    fmt,
+    io::{self, Write},
+    result::Result as StdResult,
 };
-use futures::future::join_all;
-use std::{error::Error as StdError, fmt};
 
 #[derive(Debug)]
 pub enum Error<EG: ErrType, ED: ErrType> {
     Build(EG),
     Submit(cache::submit::Error<EG>),
     Decode(usize, ED),
+    Truncated(usize, usize),
+    Io(usize, io::Error),
 }
 impl<EG: ErrType, ED: ErrType> fmt::Display for Error<ED, EG> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -22,6 +34,12 @@ impl<EG: ErrType, ED: ErrType> fmt::Display for Error<ED, EG> {
             Self::Build(e) => write!(f, "Error building template: {}", e),
             Self::Submit(e) => write!(f, "Error get mesage: {}", e),
             Self::Decode(id, e) => write!(f, "Error decode test {} message: {}", id, e),
+            Self::Truncated(id, len) => write!(
+                f,
+                "Output of test {} truncated after {} characters",
+                id, len
+            ),
+            Self::Io(id, e) => write!(f, "Error writing test {}: {}", id, e),
         }
     }
 }
@@ -31,112 +49,458 @@ impl<ED: ErrType, EG: ErrType> StdError for Error<ED, EG> {
             Self::Build(e) => Some(e),
             Self::Submit(e) => Some(e),
             Self::Decode(_, e) => Some(e),
+            Self::Truncated(_, _) => None,
+            Self::Io(_, e) => Some(e),
         }
     }
 }
-pub enum DataResult<EG: ErrType, ED: ErrType> {
+#[derive(Debug, Clone)]
+pub struct BudgetError {
+    pub exceeded: Exceeded,
+    pub remaining: Vec<SubmitKey>,
+}
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Submission budget exceeded: {}. {} chunks left:",
+            self.exceeded,
+            self.remaining.len()
+        )?;
+        for (i, k) in self.remaining.iter().enumerate() {
+            write!(f, "{} {}", if i == 0 { "" } else { "," }, k)?;
+        }
+        Ok(())
+    }
+}
+impl StdError for BudgetError {}
+
+pub type DataError = Error<SchemeError, SchemeError>;
+
+pub enum DataResult<EG: ErrType, ED: ErrType, T = Vec<u8>> {
     Build(Error<EG, ED>),
-    Result(Vec<Result<String, Error<EG, ED>>>),
+    Result(Vec<Result<T, Error<EG, ED>>>),
+    Budget(Vec<Result<T, Error<EG, ED>>>, BudgetError),
+}
+pub struct Submitted<Err: ErrType + 'static> {
+    tests: Vec<(usize, Vec<Handle<Err>>)>,
+    budget: Option<BudgetError>,
+    length: usize,
+}
+impl<Err: ErrType + 'static> Submitted<Err> {
+    pub fn handles(&self) -> impl Iterator<Item = &Handle<Err>> {
+        self.tests.iter().flat_map(|(_, h)| h.iter())
+    }
+    pub fn budget(&self) -> Option<&BudgetError> {
+        self.budget.as_ref()
+    }
+    /// Add the tests of another range submitted with the same chunk length,
+    /// to be collected after the ones already in `self`.
+    pub fn append(&mut self, other: Submitted<Err>) {
+        self.tests.extend(other.tests);
+        match (&mut self.budget, other.budget) {
+            (Some(a), Some(b)) => a.remaining.extend(b.remaining),
+            (a, b) => *a = a.take().or(b),
+        }
+    }
+}
+
+/// Number of characters the chunk at `key` prints.
+fn expected_length(data: &TestMeta, key: SubmitKey, length: usize) -> usize {
+    min(length, data.output_size.saturating_sub(key.time))
+}
+fn chunks(data: &TestMeta, index: usize, length: usize) -> impl Iterator<Item = SubmitKey> {
+    (0..data.output_size)
+        .step_by(length)
+        .map(move |x| SubmitKey {
+            test: index + 1,
+            time: x,
+        })
+}
+
+pub(super) async fn fetch<'b, Enc, Err>(
+    cache: &mut Cache,
+    data: &'b [TestMeta],
+    encoder: &mut Enc,
+    language: &str,
+    begin: usize,
+    end: usize,
+    length: usize,
+) -> (Vec<Vec<Handle<Err>>>, Option<BudgetError>)
+where
+    Enc: DataEncoder<'b, Err> + ?Sized,
+    Err: ErrType,
+{
+    for i in &data[0..begin] {
+        encoder.push_ignore(&i.data_id);
+    }
+    encoder.init();
+    let mut ret = Vec::with_capacity(end - begin);
+    let mut abort = None;
+    let mut exhausted = None;
+    for (data, index) in data[begin..end].iter().zip(begin..end) {
+        let keys = chunks(data, index, length);
+        ret.push(if data.exact_input().is_some() {
+            Vec::new()
+        } else if let Some(r) = abort {
+            cache.skip_iter(keys, || submitter::Error::aborted(r))
+        } else if let Some(e) = exhausted {
+            cache.skip_iter(keys, || submitter::Error::budget_exceeded(e))
+        } else {
+            let mut handles = cache
+                .submit_iter(keys, language, |k| encoder.generate(k.time))
+                .await;
+            for _ in 0..DUPLICATE_RETRY {
+                let duplicate: Vec<usize> = (0..handles.len())
+                    .filter(|&i| handles[i].rejection() == Some(Rejection::Duplicate))
+                    .collect();
+                if duplicate.is_empty() {
+                    break;
+                }
+                let keys: Vec<SubmitKey> = duplicate.iter().map(|&i| handles[i].key()).collect();
+                encoder.init();
+                let retry = cache
+                    .submit_iter(keys, language, |k| encoder.generate(k.time))
+                    .await;
+                for (i, h) in duplicate.into_iter().zip(retry) {
+                    handles[i] = h;
+                }
+            }
+            abort = handles.iter().find_map(|h| match h.rejection() {
+                Some(Rejection::Duplicate) | Some(Rejection::Limit) | None => None,
+                r => r,
+            });
+            exhausted = handles.iter().find_map(Handle::budget);
+            handles
+        });
+        encoder.push_ignore(&data.data_id);
+    }
+    let budget = exhausted.map(|exceeded| BudgetError {
+        exceeded,
+        remaining: ret
+            .iter()
+            .flatten()
+            .filter(|h| h.budget().is_some())
+            .map(Handle::key)
+            .collect(),
+    });
+    (ret, budget)
+}
+pub(super) fn decode_test<'a, Dec, Err, It>(
+    decoder: &mut Dec,
+    data: &TestMeta,
+    index: usize,
+    length: usize,
+    messages: It,
+    truncated: &mut Vec<SubmitKey>,
+) -> Result<Vec<u8>, Error<Err, Dec::Error>>
+where
+    Dec: DataDecoder + ?Sized,
+    Err: ErrType,
+    It: IntoIterator<Item = (SubmitKey, StdResult<&'a Verdict, cache::submit::Error<Err>>)>,
+{
+    if let Some(p) = data.exact_input() {
+        return Ok(p.as_bytes().to_vec());
+    }
+    decoder.init(data);
+    let ret = try {
+        for (k, v) in messages {
+            let v = v.map_err(Error::Submit)?;
+            if let Some(len) = v.truncated(expected_length(data, k, length)) {
+                truncated.push(k);
+                Err(Error::Truncated(index, len))?;
+            }
+            decoder.append_message(&v.output);
+        }
+        decoder.decode().map_err(|e| Error::Decode(index, e))?
+    };
+    decoder.clear();
+    ret
+}
+async fn decode<Dec: DataDecoder + ?Sized, Err: ErrType>(
+    cache: &mut Cache,
+    data: &[TestMeta],
+    decoder: &mut Dec,
+    tests: Vec<(usize, Vec<Handle<Err>>)>,
+    length: usize,
+) -> (Vec<Result<Vec<u8>, Error<Err, Dec::Error>>>, Option<usize>) {
+    let count: Vec<(usize, usize)> = tests.iter().map(|(i, h)| (*i, h.len())).collect();
+    let keys: Vec<SubmitKey> = tests
+        .iter()
+        .flat_map(|(_, h)| h.iter().map(Handle::key))
+        .collect();
+    let mut truncated = Vec::new();
+    let mut visible = None;
+    let ret = {
+        let mut verdicts = keys.into_iter().zip(
+            cache
+                .get_result(tests.into_iter().flat_map(|(_, h)| h).collect(), length)
+                .await,
+        );
+        count
+            .into_iter()
+            .map(|(index, count)| {
+                let messages: Vec<_> = verdicts.by_ref().take(count).collect();
+                let ret = decode_test(
+                    decoder,
+                    &data[index],
+                    index,
+                    length,
+                    messages,
+                    &mut truncated,
+                );
+                if let Err(Error::Truncated(_, len)) = &ret {
+                    visible = Some(*len);
+                }
+                ret
+            })
+            .collect()
+    };
+    for k in truncated.iter() {
+        cache.remove(k);
+    }
+    (ret, visible)
+}
+
+/// Like `decode_test`, but streams the test to `dest` through the writer of
+/// `scheme`, returning the number of bytes written.
+pub(super) fn write_test<'a, 'w, W, It>(
+    scheme: &dyn Scheme,
+    data: &TestMeta,
+    index: usize,
+    length: usize,
+    mut dest: W,
+    messages: It,
+    truncated: &mut Vec<SubmitKey>,
+) -> Result<usize, DataError>
+where
+    W: Write + Send + 'w,
+    It: IntoIterator<
+        Item = (
+            SubmitKey,
+            StdResult<&'a Verdict, cache::submit::Error<SchemeError>>,
+        ),
+    >,
+{
+    if let Some(p) = data.exact_input() {
+        dest.write_all(p.as_bytes())
+            .and_then(|_| dest.flush())
+            .map_err(|e| Error::Io(index, e))?;
+        return Ok(p.len());
+    }
+    let mut decoder = scheme.writer(data, Box::new(dest));
+    for (k, v) in messages {
+        let v = v.map_err(Error::Submit)?;
+        if let Some(len) = v.truncated(expected_length(data, k, length)) {
+            truncated.push(k);
+            return Err(Error::Truncated(index, len));
+        }
+        decoder
+            .append_message(&v.output)
+            .map_err(|e| Error::Decode(index, e))?;
+    }
+    decoder.finish().map_err(|e| Error::Decode(index, e))
 }
 
-impl<'a> Downloader<'a> {
-    async fn fetch<'b, 'c, Enc: DataEncoder<'b, Err>, Err: ErrType>(
-        &'c mut self,
+/// Like `decode`, but waits for one test at a time and streams it to the
+/// writer returned by `open`, passing the number of bytes written to `done`.
+/// Stops at the first error `done` returns.
+async fn decode_to<'w, W, F, D, E>(
+    cache: &mut Cache,
+    data: &[TestMeta],
+    scheme: &dyn Scheme,
+    tests: Vec<(usize, Vec<Handle<SchemeError>>)>,
+    length: usize,
+    mut open: F,
+    mut done: D,
+) -> (Result<(), E>, Option<usize>)
+where
+    W: Write + Send + 'w,
+    F: FnMut(usize) -> io::Result<W>,
+    D: FnMut(&Cache, usize, Result<usize, DataError>) -> Result<(), E>,
+{
+    let mut visible = None;
+    for (index, handles) in tests {
+        let data = &data[index];
+        let keys: Vec<SubmitKey> = handles.iter().map(Handle::key).collect();
+        let mut truncated = Vec::new();
+        let verdicts = cache.get_result(handles, length).await;
+        let result = open(index)
+            .map_err(|e| Error::Io(index, e))
+            .and_then(|dest| {
+                write_test(
+                    scheme,
+                    data,
+                    index,
+                    length,
+                    dest,
+                    keys.into_iter().zip(verdicts),
+                    &mut truncated,
+                )
+            });
+        if let Err(Error::Truncated(_, len)) = &result {
+            visible = Some(*len);
+        }
+        for k in truncated.iter() {
+            cache.remove(k);
+        }
+        if let Err(e) = done(cache, index, result) {
+            return (Err(e), visible);
+        }
+    }
+    (Ok(()), visible)
+}
+
+/// Nothing visible leaves the length alone, as chunks can't be empty.
+pub(super) fn shrink(chunk: &mut usize, cache: &mut Cache, visible: usize) {
+    if visible != 0 && visible < *chunk {
+        *chunk = visible;
+        cache.flush();
+    }
+}
+
+impl Downloader {
+    pub fn chunks(&self, index: usize) -> Vec<SubmitKey> {
+        let data = &self.data[index];
+        if data.exact_input().is_some() {
+            Vec::new()
+        } else {
+            chunks(data, index, self.chunk_length()).collect()
+        }
+    }
+    pub async fn submit_data(
+        &mut self,
+        scheme: &dyn Scheme,
         template: &Template,
         begin: usize,
         end: usize,
-    ) -> Result<Vec<Vec<Handle<Err>>>, Err>
+    ) -> Result<Submitted<SchemeError>, SchemeError> {
+        let length = self.chunk_length();
+        let mut encoder = scheme.encoder(template, end, length)?;
+        let (handles, budget) = fetch(
+            &mut self.cache,
+            &self.data,
+            &mut *encoder,
+            &template.language,
+            begin,
+            end,
+            length,
+        )
+        .await;
+        Ok(Submitted {
+            tests: (begin..).zip(handles).collect(),
+            budget,
+            length,
+        })
+    }
+    pub async fn collect_data(
+        &mut self,
+        scheme: &dyn Scheme,
+        submitted: Submitted<SchemeError>,
+    ) -> Vec<Result<Vec<u8>, DataError>> {
+        let (ret, visible) = decode(
+            &mut self.cache,
+            &self.data,
+            &mut *scheme.decoder(),
+            submitted.tests,
+            submitted.length,
+        )
+        .await;
+        if let Some(len) = visible {
+            shrink(&mut self.chunk, &mut self.cache, len);
+        }
+        ret
+    }
+    pub async fn save_collected<'w, W, F>(
+        &mut self,
+        scheme: &dyn Scheme,
+        submitted: Submitted<SchemeError>,
+        open: F,
+    ) -> Vec<Result<usize, DataError>>
     where
-        'a: 'c,
-        'c: 'b,
+        W: Write + Send + 'w,
+        F: FnMut(usize) -> io::Result<W>,
     {
-        let mut encoder = Enc::new(template, end)?;
-        for i in &self.data[0..begin] {
-            encoder.push_ignore(&i.data_id);
-        }
-        encoder.init();
-        let cache: *mut Cache<'a> = &mut self.cache;
-        let encoder_ptr: *mut Enc = &mut encoder;
-        Ok(join_all(self.data[begin..end].iter().zip(begin..end).map(
-            async move |(data, index)| {
-                let ret = if data.input.is_none() {
-                    unsafe { &mut *cache }
-                        .submit_iter(
-                            (0..data.output_size).step_by(BLOCK).map(|x| SubmitKey {
-                                test: index + 1,
-                                time: x,
-                            }),
-                            template.language.as_str(),
-                            |k| unsafe { &mut *encoder_ptr }.generate(k.time),
-                        )
-                        .await
-                } else {
-                    Vec::new()
-                };
-                unsafe { &mut *encoder_ptr }.push_ignore(&data.data_id);
-                ret
-            },
-        ))
-        .await)
+        let mut ret = Vec::with_capacity(submitted.tests.len());
+        let _ = self
+            .save_each(scheme, submitted, open, |_, _, v| {
+                ret.push(v);
+                Ok::<(), Infallible>(())
+            })
+            .await;
+        ret
     }
-    async fn decode<Dec: DataDecoder, Err: ErrType>(
+    /// Like `save_collected`, but hands each test to `done` as soon as it is
+    /// written, together with the cache, which no longer holds its pending
+    /// submissions. Stops at the first error `done` returns.
+    pub async fn save_each<'w, W, F, D, E>(
         &mut self,
-        begin: usize,
-        handles: Vec<Vec<Handle<Err>>>,
-    ) -> Vec<Result<String, Error<Err, Dec::Error>>> {
-        let mut decoder = Dec::new();
-        let cache: *mut Cache<'_> = &mut self.cache;
-        let data_ptr = &self.data;
-        let decoder_ptr: *mut Dec = &mut decoder;
-        join_all(
-            handles
-                .into_iter()
-                .enumerate()
-                .map(async move |(i, handle)| {
-                    let data = &*data_ptr;
-                    if let Some(p) = &data[begin + i].input {
-                        Ok(p.clone())
-                    } else {
-                        let decoder = unsafe { &mut *decoder_ptr };
-                        decoder.init(&data[begin + i]);
-                        let ret = try {
-                            unsafe { &mut *cache }
-                                .get_result(handle)
-                                .await
-                                .into_iter()
-                                .try_for_each(|v| {
-                                    decoder.append_message(v.map_err(Error::Submit)?.output.trim());
-                                    Ok(())
-                                })?;
-                            decoder.decode().map_err(|e| Error::Decode(begin + i, e))?
-                        };
-                        decoder.clear();
-                        ret
-                    }
-                }),
+        scheme: &dyn Scheme,
+        submitted: Submitted<SchemeError>,
+        open: F,
+        done: D,
+    ) -> Result<(), E>
+    where
+        W: Write + Send + 'w,
+        F: FnMut(usize) -> io::Result<W>,
+        D: FnMut(&Cache, usize, Result<usize, DataError>) -> Result<(), E>,
+    {
+        let (ret, visible) = decode_to(
+            &mut self.cache,
+            &self.data,
+            scheme,
+            submitted.tests,
+            submitted.length,
+            open,
+            done,
         )
-        .await
+        .await;
+        if let Some(len) = visible {
+            shrink(&mut self.chunk, &mut self.cache, len);
+        }
+        ret
     }
-    pub async fn get_data<'c, 'b, Enc, Dec, Err>(
-        &'c mut self,
+    /// Like `get_data`, but writes each test through `open` as soon as its
+    /// chunks are judged instead of keeping the decoded range in memory.
+    pub async fn save_data<'w, W, F>(
+        &mut self,
+        scheme: &dyn Scheme,
         template: &Template,
         begin: usize,
         end: usize,
-    ) -> DataResult<Err, Dec::Error>
+        open: F,
+    ) -> DataResult<SchemeError, SchemeError, usize>
     where
-        Enc: DataEncoder<'b, Err>,
-        Dec: DataDecoder,
-        Err: ErrType,
-        'a: 'c,
-        'c: 'b,
+        W: Write + Send + 'w,
+        F: FnMut(usize) -> io::Result<W>,
     {
-        let self_ptr: *mut Self = self;
-        match unsafe { &mut *self_ptr }
-            .fetch::<Enc, Err>(template, begin, end)
-            .await
-        {
-            Ok(v) => DataResult::Result(self.decode::<Dec, Err>(begin, v).await),
-            Err(e) => DataResult::Build(Error::Build(e)),
+        let submitted = match self.submit_data(scheme, template, begin, end).await {
+            Ok(v) => v,
+            Err(e) => return DataResult::Build(Error::Build(e)),
+        };
+        let budget = submitted.budget.clone();
+        let ret = self.save_collected(scheme, submitted, open).await;
+        match budget {
+            None => DataResult::Result(ret),
+            Some(b) => DataResult::Budget(ret, b),
+        }
+    }
+    pub async fn get_data(
+        &mut self,
+        scheme: &dyn Scheme,
+        template: &Template,
+        begin: usize,
+        end: usize,
+    ) -> DataResult<SchemeError, SchemeError> {
+        let submitted = match self.submit_data(scheme, template, begin, end).await {
+            Ok(v) => v,
+            Err(e) => return DataResult::Build(Error::Build(e)),
+        };
+        let budget = submitted.budget.clone();
+        let ret = self.collect_data(scheme, submitted).await;
+        match budget {
+            None => DataResult::Result(ret),
+            Some(b) => DataResult::Budget(ret, b),
         }
     }
 }
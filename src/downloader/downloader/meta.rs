@@ -1,9 +1,15 @@
 use super::Downloader;
 
 use crate::{
-    encoding::{MetaEncoding, Template},
+    cache::SubmitKey,
+    config::{downloader::DUPLICATE_RETRY, encoding::DEFAULT_SCHEME},
+    encoding::{
+        scheme::{Error as SchemeError, Scheme},
+        Template,
+    },
     error::Error as ErrType,
-    judge, submitter,
+    judge::{self, submit::Rejection},
+    submitter::{self, audit::Purpose},
 };
 use std::{error::Error as StdError, fmt};
 
@@ -14,6 +20,7 @@ enum Kind<E: ErrType + 'static> {
     Generate(E),
     GetResult(judge::Error),
     Decode(E),
+    Scheme(String, String),
 }
 #[derive(Debug)]
 pub struct Error<E: ErrType + 'static> {
@@ -28,6 +35,11 @@ impl<E: ErrType + 'static> fmt::Display for Error<E> {
             Kind::Submit(e) => write!(f, "Error submit code for {}: {}", self.id, e),
             Kind::GetResult(e) => write!(f, "Error getting result for {}: {}", self.id, e),
             Kind::Decode(e) => write!(f, "Error decoding result for {}: {}", self.id, e),
+            Kind::Scheme(stored, requested) => write!(
+                f,
+                "Metadata was collected with scheme {}, not {}",
+                stored, requested
+            ),
         }
     }
 }
@@ -39,6 +51,7 @@ impl<E: ErrType + 'static> StdError for Error<E> {
             Kind::Submit(e) => Some(e),
             Kind::GetResult(e) => Some(e),
             Kind::Decode(e) => Some(e),
+            Kind::Scheme(_, _) => None,
         }
     }
 }
@@ -54,23 +67,31 @@ impl<E: ErrType> Error<E> {
     }
 }
 
-impl<'a> Downloader<'a> {
-    pub async fn get_meta<'b, Enc, Err>(
+impl Downloader {
+    pub async fn get_meta(
         &mut self,
+        scheme: &dyn Scheme,
         template: &Template,
         end: usize,
-    ) -> Result<(), Error<Err>>
-    where
-        Enc: MetaEncoding<'b, Err>,
-        Err: ErrType,
-    {
+    ) -> Result<(), Error<SchemeError>> {
         if end < self.len() {
             return Ok(());
         }
         let base = self.data.len();
+        // Meta files written before schemes were recorded used the default.
+        let stored = self.scheme().unwrap_or(DEFAULT_SCHEME);
+        if base != 0 && stored != scheme.name() {
+            return Err(Error::new(
+                base,
+                Kind::Scheme(stored.to_string(), scheme.name().to_string()),
+            ));
+        }
         let count = end - base;
         self.data.reserve(count);
-        let mut enc = Enc::new(template, count + base).map_err(Error::from_build)?;
+        let mut enc = scheme
+            .meta(template, count + base)
+            .map_err(Error::from_build)?;
+        self.scheme = Some(scheme.name().to_string());
         unsafe {
             for i in 0..base {
                 enc.ignore(&(*self.data.as_ptr().add(i)).data_id);
@@ -78,24 +99,46 @@ impl<'a> Downloader<'a> {
         }
         enc.init();
         for id in base..base + count {
+            let mut retry = 0;
+            let submission = loop {
+                match self
+                    .cache
+                    .submitter
+                    .submit(
+                        &self.problem,
+                        &template.language,
+                        enc.generate()
+                            .map_err(|e| Error::new(id, Kind::Generate(e)))?
+                            .as_str(),
+                        SubmitKey {
+                            test: id + 1,
+                            time: 0,
+                        },
+                        Purpose::Meta,
+                    )
+                    .await
+                {
+                    Err(e)
+                        if e.rejection() == Some(Rejection::Duplicate)
+                            && retry < DUPLICATE_RETRY =>
+                    {
+                        retry += 1;
+                        enc.init();
+                    }
+                    v => break v.map_err(|e| Error::new(id, Kind::Submit(e)))?,
+                }
+            };
+            let verdict = submission
+                .wait(id + 1, self.chunk)
+                .await
+                .map_err(|e| Error::new(id, Kind::GetResult(e)))?;
+            self.cache
+                .submitter
+                .audit_verdict(&submission, verdict.verdict());
             self.data.push(
-                Enc::decode(
-                    self.cache
-                        .submitter
-                        .submit(
-                            &self.problem,
-                            &template.language,
-                            enc.generate()
-                                .map_err(|e| Error::new(id, Kind::Generate(e)))?
-                                .as_str(),
-                        )
-                        .await
-                        .map_err(|e| Error::new(id, Kind::Submit(e)))?
-                        .wait(id+1)
-                        .await
-                        .map_err(|e| Error::new(id, Kind::GetResult(e)))?,
-                )
-                .map_err(|e| Error::new(id, Kind::Decode(e)))?,
+                scheme
+                    .decode_meta(verdict)
+                    .map_err(|e| Error::new(id, Kind::Decode(e)))?,
             );
             unsafe {
                 enc.ignore(&(*self.data.as_ptr().add(id)).data_id);
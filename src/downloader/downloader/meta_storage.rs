@@ -2,13 +2,14 @@ extern crate serde;
 extern crate serde_yaml;
 
 use super::Downloader;
-use crate::{judge::problem::Problem, types::TestMeta};
+use crate::{config::chunk::DEFAULT_LENGTH, judge::problem::Problem, types::TestMeta};
 use serde::{Deserialize, Serialize};
 use serde_yaml::{from_reader, to_writer};
 use std::{
     error::Error as StdError,
     fmt,
     io::{Read, Write},
+    num::NonZeroUsize,
 };
 
 #[derive(Debug)]
@@ -38,21 +39,32 @@ impl StdError for Error {
 #[derive(Serialize)]
 struct SaveContent<'a> {
     problem: &'a Problem,
+    scheme: Option<&'a str>,
+    chunk: usize,
     data: &'a Vec<TestMeta>,
 }
 #[derive(Deserialize)]
 struct LoadContent {
     problem: Problem,
+    #[serde(default)]
+    scheme: Option<String>,
+    #[serde(default = "default_chunk")]
+    chunk: NonZeroUsize,
     data: Vec<TestMeta>,
 }
+fn default_chunk() -> NonZeroUsize {
+    NonZeroUsize::new(DEFAULT_LENGTH).unwrap()
+}
 
-impl<'a> Downloader<'a> {
+impl Downloader {
     pub fn load_meta<R: Read>(&mut self, rdr: R) -> Result<(), Error> {
         let lst: LoadContent = from_reader(rdr).map_err(Error::Yaml)?;
         if &lst.problem != self.problem.as_ref() {
             Err(Error::Mismatch(self.problem.as_ref().clone(), lst.problem))
         } else {
             self.data = lst.data;
+            self.scheme = lst.scheme;
+            self.set_chunk_length(lst.chunk);
             Ok(())
         }
     }
@@ -61,6 +73,8 @@ impl<'a> Downloader<'a> {
             wdr,
             &SaveContent {
                 problem: &self.problem,
+                scheme: self.scheme(),
+                chunk: self.chunk_length(),
                 data: &self.data,
             },
         )
@@ -0,0 +1,55 @@
+use super::Downloader;
+use crate::config::downloader::CONFIRM_SUBMISSIONS;
+use std::time::Duration;
+
+pub struct TestPlan {
+    pub index: usize,
+    pub chunks: usize,
+    pub cached: usize,
+}
+impl TestPlan {
+    pub fn needed(&self) -> usize {
+        self.chunks - self.cached
+    }
+}
+pub struct Plan {
+    pub tests: Vec<TestPlan>,
+    pub accounts: usize,
+    pub estimate: Duration,
+}
+impl Plan {
+    pub fn chunks(&self) -> usize {
+        self.tests.iter().map(|x| x.chunks).sum()
+    }
+    pub fn cached(&self) -> usize {
+        self.tests.iter().map(|x| x.cached).sum()
+    }
+    pub fn submissions(&self) -> usize {
+        self.tests.iter().map(TestPlan::needed).sum()
+    }
+    pub fn need_confirm(&self) -> bool {
+        self.submissions() > CONFIRM_SUBMISSIONS
+    }
+}
+
+impl Downloader {
+    pub fn plan(&self, begin: usize, end: usize) -> Plan {
+        let tests: Vec<TestPlan> = (begin..end)
+            .map(|index| {
+                let keys = self.chunks(index);
+                TestPlan {
+                    index,
+                    chunks: keys.len(),
+                    cached: keys.iter().filter(|k| self.cache.contains(k)).count(),
+                }
+            })
+            .collect();
+        let submissions = tests.iter().map(TestPlan::needed).sum();
+        let submitter = &self.cache.submitter;
+        Plan {
+            tests,
+            accounts: submitter.online_count(),
+            estimate: submitter.estimate(submissions),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+extern crate futures;
+
+use super::{
+    data::{decode_test, fetch, shrink, write_test, BudgetError, DataError, Error},
+    Downloader,
+};
+use crate::{
+    cache::{
+        self,
+        submit::{Handle, Judged},
+        Cache, SubmitKey,
+    },
+    encoding::{
+        scheme::{BoxDecoder, Error as SchemeError, Scheme},
+        Template,
+    },
+    error::Error as ErrType,
+    judge::Verdict,
+    types::TestMeta,
+};
+use futures::{
+    future::{join_all, BoxFuture},
+    stream::{self, BoxStream, FuturesUnordered},
+    task::{Context, Poll},
+    FutureExt, Stream, StreamExt,
+};
+use std::{
+    io::{self, Write},
+    pin::Pin,
+    result::Result as StdResult,
+};
+
+pub type Item<EG, ED, T = Vec<u8>> = (usize, Result<T, Error<EG, ED>>);
+
+/// Tests of a range in the order their chunks are judged. Nothing is decoded
+/// until the stream is polled, and dropping it stops waiting for the rest of
+/// the tests, whose submissions stay pending in the cache.
+pub struct DataStream<'b, EG: ErrType + 'static, ED: ErrType, T = Vec<u8>> {
+    inner: BoxStream<'b, Item<EG, ED, T>>,
+    budget: Option<BudgetError>,
+}
+impl<'b, EG: ErrType + 'static, ED: ErrType, T> DataStream<'b, EG, ED, T> {
+    /// Set if the budget ran out while submitting, tests missing chunks then
+    /// yield submit errors.
+    pub fn budget(&self) -> Option<&BudgetError> {
+        self.budget.as_ref()
+    }
+}
+impl<'b, EG: ErrType + 'static, ED: ErrType, T> Stream for DataStream<'b, EG, ED, T> {
+    type Item = Item<EG, ED, T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+type Pending<Err> = BoxFuture<'static, (usize, Vec<SubmitKey>, Vec<Judged<Err>>)>;
+type Verdicts<'a> = Vec<(
+    SubmitKey,
+    StdResult<&'a Verdict, cache::submit::Error<SchemeError>>,
+)>;
+
+/// What a stream makes of the judged chunks of a test.
+trait Output {
+    type Item;
+    fn test(
+        &mut self,
+        data: &TestMeta,
+        index: usize,
+        length: usize,
+        messages: Verdicts<'_>,
+        truncated: &mut Vec<SubmitKey>,
+    ) -> Result<Self::Item, DataError>;
+}
+/// Decoded in memory.
+struct Bytes(BoxDecoder);
+impl Output for Bytes {
+    type Item = Vec<u8>;
+
+    fn test(
+        &mut self,
+        data: &TestMeta,
+        index: usize,
+        length: usize,
+        messages: Verdicts<'_>,
+        truncated: &mut Vec<SubmitKey>,
+    ) -> Result<Vec<u8>, DataError> {
+        decode_test(&mut *self.0, data, index, length, messages, truncated)
+    }
+}
+/// Streamed to the writer `open` returns for the test.
+struct Files<'b, F> {
+    scheme: &'b dyn Scheme,
+    open: F,
+}
+impl<'b, W, F> Output for Files<'b, F>
+where
+    W: Write + Send + 'b,
+    F: FnMut(usize) -> io::Result<W>,
+{
+    type Item = usize;
+
+    fn test(
+        &mut self,
+        data: &TestMeta,
+        index: usize,
+        length: usize,
+        messages: Verdicts<'_>,
+        truncated: &mut Vec<SubmitKey>,
+    ) -> Result<usize, DataError> {
+        let dest = (self.open)(index).map_err(|e| Error::Io(index, e))?;
+        write_test(self.scheme, data, index, length, dest, messages, truncated)
+    }
+}
+
+struct State<'b, O> {
+    cache: &'b mut Cache,
+    chunk: &'b mut usize,
+    length: usize,
+    data: &'b [TestMeta],
+    output: O,
+    pending: FuturesUnordered<Pending<SchemeError>>,
+    visible: Option<usize>,
+}
+impl<'b, O: Output> State<'b, O> {
+    async fn next(mut self) -> Option<(Item<SchemeError, SchemeError, O::Item>, Self)> {
+        let (index, keys, judged) = match self.pending.next().await {
+            Some(v) => v,
+            None => {
+                // Cached chunks of other tests are only dropped once none
+                // of them is in flight any more.
+                if let Some(len) = self.visible {
+                    shrink(self.chunk, self.cache, len);
+                }
+                return None;
+            }
+        };
+        let mut truncated = Vec::new();
+        let ret = self.output.test(
+            &self.data[index],
+            index,
+            self.length,
+            keys.into_iter().zip(self.cache.record(judged)).collect(),
+            &mut truncated,
+        );
+        for k in truncated.iter() {
+            self.cache.remove(k);
+        }
+        if let Err(Error::Truncated(_, len)) = &ret {
+            self.visible = Some(*len);
+        }
+        Some(((index, ret), self))
+    }
+}
+
+impl Downloader {
+    /// Submit the chunks of `begin..end` and yield each test as soon as all
+    /// of its chunks are judged.
+    pub async fn stream_data<'b>(
+        &'b mut self,
+        scheme: &dyn Scheme,
+        template: &Template,
+        begin: usize,
+        end: usize,
+    ) -> Result<DataStream<'b, SchemeError, SchemeError>, SchemeError> {
+        let output = Bytes(scheme.decoder());
+        self.stream(scheme, template, begin, end, output).await
+    }
+    /// Like `stream_data`, but writes each test to the writer `open` returns
+    /// for it and yields the number of bytes written, so that no test is
+    /// kept in memory.
+    pub async fn stream_to<'b, W, F>(
+        &'b mut self,
+        scheme: &'b dyn Scheme,
+        template: &Template,
+        begin: usize,
+        end: usize,
+        open: F,
+    ) -> Result<DataStream<'b, SchemeError, SchemeError, usize>, SchemeError>
+    where
+        W: Write + Send + 'b,
+        F: FnMut(usize) -> io::Result<W> + Send + 'b,
+    {
+        let output = Files { scheme, open };
+        self.stream(scheme, template, begin, end, output).await
+    }
+    async fn stream<'b, O>(
+        &'b mut self,
+        scheme: &dyn Scheme,
+        template: &Template,
+        begin: usize,
+        end: usize,
+        output: O,
+    ) -> Result<DataStream<'b, SchemeError, SchemeError, O::Item>, SchemeError>
+    where
+        O: Output + Send + 'b,
+        O::Item: Send + 'b,
+    {
+        let length = self.chunk;
+        let Downloader {
+            data, cache, chunk, ..
+        } = self;
+        let data: &'b [TestMeta] = data;
+        let mut encoder = scheme.encoder(template, end, length)?;
+        let (handles, budget) = fetch(
+            cache,
+            data,
+            &mut *encoder,
+            &template.language,
+            begin,
+            end,
+            length,
+        )
+        .await;
+        let pending = FuturesUnordered::new();
+        for (index, handles) in (begin..).zip(handles) {
+            pending.push(
+                async move {
+                    let keys = handles.iter().map(Handle::key).collect();
+                    let judged = join_all(handles.into_iter().map(|h| h.judge(length))).await;
+                    (index, keys, judged)
+                }
+                .boxed(),
+            );
+        }
+        let state = State {
+            cache,
+            chunk,
+            length,
+            data,
+            output,
+            pending,
+            visible: None,
+        };
+        Ok(DataStream {
+            inner: stream::unfold(state, State::next).boxed(),
+            budget,
+        })
+    }
+}
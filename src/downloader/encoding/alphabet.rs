@@ -0,0 +1,131 @@
+extern crate base64;
+extern crate serde;
+
+use base64::{decode_config_buf, DecodeError};
+use serde::{Deserialize, Serialize};
+use std::{error::Error as StdError, fmt, str::FromStr};
+
+const Z85: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+const BASE95_FIRST: u8 = b' ';
+
+#[derive(Debug)]
+pub enum Error {
+    Base64(DecodeError),
+    Symbol(Alphabet, usize, char),
+    Length(Alphabet, usize),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base64(e) => write!(f, "base64: {}", e),
+            Self::Symbol(a, pos, c) => write!(f, "{}: invalid symbol {:?} at {}", a, c, pos),
+            Self::Length(a, len) => write!(f, "{}: invalid length {}", a, len),
+        }
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Base64(e) => Some(e),
+            Self::Symbol(_, _, _) | Self::Length(_, _) => None,
+        }
+    }
+}
+
+/// Text encoding of the compressed test, each alphabet encodes whole groups
+/// of bytes and the encoder pads the last group with zeros.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Alphabet {
+    #[default]
+    Base64,
+    Z85,
+    Base95,
+}
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Base64 => "base64",
+            Self::Z85 => "z85",
+            Self::Base95 => "base95",
+        })
+    }
+}
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "base64" => Ok(Self::Base64),
+            "z85" => Ok(Self::Z85),
+            "base95" => Ok(Self::Base95),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl Alphabet {
+    /// (bytes, symbols) per group.
+    fn group(&self) -> (usize, usize) {
+        match self {
+            Self::Base64 => (3, 4),
+            Self::Z85 => (4, 5),
+            Self::Base95 => (9, 11),
+        }
+    }
+    /// Length of the prefix made of whole symbol groups.
+    pub fn whole_groups(&self, len: usize) -> usize {
+        len - len % self.group().1
+    }
+    fn base(&self) -> u128 {
+        match self {
+            Self::Base64 => 64,
+            Self::Z85 => 85,
+            Self::Base95 => 95,
+        }
+    }
+    fn digit(&self, c: u8) -> Option<u128> {
+        match self {
+            Self::Base64 => None,
+            Self::Z85 => Z85.iter().position(|&x| x == c).map(|x| x as u128),
+            Self::Base95 => match c {
+                b' '..=b'~' => Some((c - BASE95_FIRST) as u128),
+                _ => None,
+            },
+        }
+    }
+    /// Strip what the judge adds around the output. Base95 uses space as a
+    /// symbol, so only line breaks are removed.
+    pub fn trim<'a>(&self, message: &'a str) -> &'a str {
+        match self {
+            Self::Base95 => message.trim_matches(|c| c == '\r' || c == '\n'),
+            _ => message.trim(),
+        }
+    }
+    pub fn decode(&self, input: &[u8], dest: &mut Vec<u8>) -> Result<(), Error> {
+        if let Self::Base64 = self {
+            return decode_config_buf(input, base64::STANDARD, dest).map_err(Error::Base64);
+        }
+        let (bytes, symbols) = self.group();
+        let groups = input.chunks_exact(symbols);
+        if !groups.remainder().is_empty() {
+            return Err(Error::Length(*self, input.len()));
+        }
+        dest.reserve(input.len() / symbols * bytes);
+        for (index, group) in groups.enumerate() {
+            let mut value: u128 = 0;
+            for (pos, &c) in group.iter().enumerate() {
+                let digit =
+                    self.digit(c)
+                        .ok_or(Error::Symbol(*self, index * symbols + pos, c as char))?;
+                value = value * self.base() + digit;
+            }
+            if value >> (bytes * 8) != 0 {
+                return Err(Error::Symbol(*self, index * symbols, group[0] as char));
+            }
+            dest.extend((0..bytes).rev().map(|i| (value >> (i * 8)) as u8));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+extern crate brotli_decompressor;
+extern crate flate2;
+extern crate lzma_rs;
+extern crate ruzstd;
+extern crate serde;
+
+use crate::{
+    encoding::{
+        alphabet::{self, Alphabet},
+        traits::DataDecoder,
+    },
+    types::TestMeta,
+};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error as StdError,
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    vec::Vec,
+};
+
+pub(crate) const BROTLI_BUFFER: usize = 4096;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    #[default]
+    Gzip,
+    Zlib,
+    Deflate,
+    Xz,
+    Zstd,
+    Brotli,
+}
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Gzip => "gzip",
+            Self::Zlib => "zlib",
+            Self::Deflate => "deflate",
+            Self::Xz => "xz",
+            Self::Zstd => "zstd",
+            Self::Brotli => "brotli",
+        })
+    }
+}
+impl FromStr for Codec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        match s {
+            "gzip" => Ok(Self::Gzip),
+            "zlib" => Ok(Self::Zlib),
+            "deflate" => Ok(Self::Deflate),
+            "xz" => Ok(Self::Xz),
+            "zstd" => Ok(Self::Zstd),
+            "brotli" => Ok(Self::Brotli),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+impl Codec {
+    pub fn decompress<W: Write>(&self, data: &[u8], dest: &mut W) -> io::Result<u64> {
+        match self {
+            Self::Gzip => io::copy(&mut GzDecoder::new(data), dest),
+            Self::Zlib => io::copy(&mut ZlibDecoder::new(data), dest),
+            Self::Deflate => io::copy(&mut DeflateDecoder::new(data), dest),
+            Self::Xz => {
+                let mut dest = Counter::new(dest);
+                lzma_rs::xz_decompress(&mut io::BufReader::new(data), &mut dest)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+                Ok(dest.count() as u64)
+            }
+            Self::Zstd => io::copy(
+                &mut ruzstd::streaming_decoder::StreamingDecoder::new(data)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?,
+                dest,
+            ),
+            Self::Brotli => io::copy(
+                &mut brotli_decompressor::Decompressor::new(data, BROTLI_BUFFER),
+                dest,
+            ),
+        }
+    }
+}
+
+/// Writer counting the bytes passed through.
+pub(crate) struct Counter<W: Write> {
+    inner: W,
+    count: usize,
+}
+impl<W: Write> Counter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+}
+impl<W: Write> Write for Counter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.count += len;
+        Ok(len)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+#[derive(Debug)]
+pub enum Error {
+    Decode(alphabet::Error),
+    Decompress(Codec, io::Error),
+    Size(usize, usize),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Decode(dec) => write!(f, "{}", dec),
+            Error::Decompress(codec, err) => write!(f, "{}: {}", codec, err),
+            Error::Size(expected, got) => {
+                write!(f, "Expected {} bytes, decoded {} bytes", expected, got)
+            }
+        }
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Decode(x) => Some(x),
+            Error::Decompress(_, x) => Some(x),
+            Error::Size(_, _) => None,
+        }
+    }
+}
+type Result<T> = std::result::Result<T, Error>;
+
+pub struct Decoder {
+    buffer: Vec<u8>,
+    decoded: Vec<u8>,
+    output_size: usize,
+    compress_size: usize,
+    codec: Codec,
+    alphabet: Alphabet,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Decoder {
+            buffer: Vec::new(),
+            decoded: Vec::new(),
+            output_size: 0,
+            compress_size: 0,
+            codec: Codec::default(),
+            alphabet: Alphabet::default(),
+        }
+    }
+}
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl DataDecoder for Decoder {
+    type Error = Error;
+
+    fn init(&mut self, data: &TestMeta) {
+        self.buffer.reserve(data.output_size);
+        self.decoded.reserve(data.compress_size);
+        self.output_size = data.size;
+        self.compress_size = data.compress_size;
+        self.codec = data.codec;
+        self.alphabet = data.alphabet;
+    }
+    fn append_message(&mut self, message: &str) {
+        self.buffer
+            .extend_from_slice(self.alphabet.trim(message).as_bytes());
+    }
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.decoded.clear();
+    }
+    fn decode(&mut self) -> Result<Vec<u8>> {
+        self.alphabet
+            .decode(&self.buffer, &mut self.decoded)
+            .map_err(Error::Decode)?;
+        self.decoded.truncate(self.compress_size);
+        let mut ret = Vec::with_capacity(self.output_size);
+        self.codec
+            .decompress(self.decoded.as_slice(), &mut ret)
+            .map_err(|e| Error::Decompress(self.codec, e))?;
+        if ret.len() != self.output_size {
+            return Err(Error::Size(self.output_size, ret.len()));
+        }
+        Ok(ret)
+    }
+}
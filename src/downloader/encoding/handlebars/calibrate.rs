@@ -0,0 +1,34 @@
+extern crate serde;
+
+use super::{
+    engine::engine,
+    error::{rander_error, Error, Result},
+};
+use crate::{
+    encoding::{traits, Template},
+    random::random_standard,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CalibrateParam {
+    mode: &'static str,
+    random: u64,
+    length: usize,
+}
+pub struct Calibrate;
+
+impl traits::Calibration<Error> for Calibrate {
+    fn generate(template: &Template, length: usize) -> Result<String> {
+        engine(template)?
+            .render(
+                "code",
+                &CalibrateParam {
+                    mode: "calibrate",
+                    random: random_standard(),
+                    length,
+                },
+            )
+            .map_err(rander_error)
+    }
+}
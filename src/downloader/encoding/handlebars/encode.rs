@@ -1,11 +1,14 @@
 extern crate handlebars;
 extern crate serde;
 
-use super::error::{rander_error, template_error, Error, Result};
+use super::{
+    engine::engine,
+    error::{rander_error, Error, Result},
+};
 use crate::{
     encoding::{traits, Template},
     random::random_standard,
-    types::{DataId, BLOCK},
+    types::DataId,
 };
 use handlebars::Handlebars;
 use serde::Serialize;
@@ -13,6 +16,7 @@ use std::vec::Vec;
 
 #[derive(Serialize)]
 struct EncParam<'a, 'b> {
+    mode: &'static str,
     random: u64,
     length: usize,
     offset: usize,
@@ -25,19 +29,17 @@ pub struct Encoder<'a> {
     engine: Handlebars<'a>,
 }
 
-impl<'a> traits::DataEncoder<'a, Error> for Encoder<'a> {
-    fn new(template: &Template, max: usize) -> Result<Self> {
-        let mut ret = Encoder {
+impl<'a> Encoder<'a> {
+    pub fn new(template: &Template, max: usize, length: usize) -> Result<Self> {
+        Ok(Encoder {
             random: 0,
-            length: BLOCK,
+            length,
             ignore: Vec::with_capacity(max),
-            engine: Handlebars::new(),
-        };
-        ret.engine
-            .register_template_string("code", template.content.as_str())
-            .map_err(template_error)?;
-        Ok(ret)
+            engine: engine(template)?,
+        })
     }
+}
+impl<'a> traits::DataEncoder<'a, Error> for Encoder<'a> {
     fn init(&mut self) {
         self.random = random_standard();
     }
@@ -52,6 +54,7 @@ impl<'a> traits::DataEncoder<'a, Error> for Encoder<'a> {
             .render(
                 "code",
                 &EncParam {
+                    mode: "data",
                     random: self.random,
                     length: self.length,
                     offset,
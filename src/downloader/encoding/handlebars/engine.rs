@@ -0,0 +1,22 @@
+extern crate handlebars;
+
+use super::{
+    error::{template_error, Result},
+    escape,
+};
+use crate::encoding::Template;
+use handlebars::Handlebars;
+
+/// Engine with `template` registered as `code`, along with its partials and
+/// the escaping helpers.
+pub(super) fn engine<'a>(template: &Template) -> Result<Handlebars<'a>> {
+    let mut ret = Handlebars::new();
+    escape::register(&mut ret);
+    ret.register_template_string("code", template.content.as_str())
+        .map_err(template_error)?;
+    for (name, content) in template.partials.iter() {
+        ret.register_partial(name, content.as_str())
+            .map_err(template_error)?;
+    }
+    Ok(ret)
+}
@@ -6,9 +6,11 @@ use std::{error::Error as StdError, fmt, num::ParseIntError, result::Result as S
 #[derive(Debug)]
 pub enum Error {
     ParseInt(&'static str, ParseIntError),
-    Template(TemplateError),
-    Rander(RenderError),
+    Template(Box<TemplateError>),
+    Rander(Box<RenderError>),
     Split(&'static str),
+    Codec(String),
+    Alphabet(String),
 }
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -17,6 +19,8 @@ impl fmt::Display for Error {
             Error::Template(err) => write!(f, "Error parsing template: {}", err),
             Error::Rander(err) => write!(f, "Error generating code: {}", err),
             Error::Split(name) => write!(f, "Can't find {}", name),
+            Error::Codec(name) => write!(f, "Unknown codec {}", name),
+            Error::Alphabet(name) => write!(f, "Unknown alphabet {}", name),
         }
     }
 }
@@ -24,9 +28,9 @@ impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             Error::ParseInt(_, err) => Some(err),
-            Error::Template(err) => Some(err),
-            Error::Rander(err) => Some(err),
-            Error::Split(_) => None,
+            Error::Template(err) => Some(err.as_ref()),
+            Error::Rander(err) => Some(err.as_ref()),
+            Error::Split(_) | Error::Codec(_) | Error::Alphabet(_) => None,
         }
     }
 }
@@ -34,8 +38,8 @@ impl StdError for Error {
 pub type Result<T> = StdResult<T, Error>;
 
 pub(super) fn template_error(error: TemplateError) -> Error {
-    Error::Template(error)
+    Error::Template(Box::new(error))
 }
 pub(super) fn rander_error(error: RenderError) -> Error {
-    Error::Rander(error)
+    Error::Rander(Box::new(error))
 }
@@ -0,0 +1,140 @@
+//! Source literals for strings that may contain anything, such as answers.
+//!
+//! Quoted literals only contain printable ASCII, so neither the source
+//! encoding nor line ending conversion can change them.
+extern crate handlebars;
+
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
+};
+use std::fmt::Write;
+
+fn quoted<F: Fn(&mut String, char)>(value: &str, escape: F) -> String {
+    let mut ret = String::with_capacity(value.len() + 2);
+    ret.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            ' '..='~' => ret.push(c),
+            _ => escape(&mut ret, c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+fn utf16(dest: &mut String, c: char) {
+    for u in c.encode_utf16(&mut [0; 2]) {
+        write!(dest, "\\u{:04x}", u).unwrap();
+    }
+}
+
+/// C# regular string literal.
+pub fn csharp(value: &str) -> String {
+    quoted(value, utf16)
+}
+/// Java string literal. Control characters use octal escapes, as `\u`
+/// escapes are translated before the literal is parsed.
+pub fn java(value: &str) -> String {
+    quoted(value, |dest, c| {
+        if c.is_ascii() {
+            write!(dest, "\\{:03o}", c as u32).unwrap();
+        } else {
+            utf16(dest, c);
+        }
+    })
+}
+/// Scala string literal.
+pub fn scala(value: &str) -> String {
+    quoted(value, utf16)
+}
+/// Python 3 string literal.
+pub fn python(value: &str) -> String {
+    quoted(value, |dest, c| {
+        match c as u32 {
+            v @ 0..=0xff => write!(dest, "\\x{:02x}", v),
+            v @ 0x100..=0xffff => write!(dest, "\\u{:04x}", v),
+            v => write!(dest, "\\U{:08x}", v),
+        }
+        .unwrap()
+    })
+}
+/// C++ raw string literal, with a delimiter that doesn't occur in `value`.
+/// Compilers may convert line endings inside it, use `bytes` if `\r`
+/// matters.
+pub fn cpp_raw(value: &str) -> String {
+    let delimiter = (0..)
+        .map(|i| match i {
+            0 => String::new(),
+            i => format!("cf{}", i),
+        })
+        .find(|d| !value.contains(&format!("){}\"", d)))
+        .unwrap();
+    format!("R\"{0}({1}){0}\"", delimiter, value)
+}
+/// Comma separated bytes of `value`, as `i8` if `signed`.
+pub fn bytes(value: &str, signed: bool) -> String {
+    let mut ret = String::with_capacity(value.len() * 4);
+    for (i, b) in value.bytes().enumerate() {
+        if i != 0 {
+            ret.push_str(", ");
+        }
+        if signed {
+            write!(ret, "{}", b as i8).unwrap();
+        } else {
+            write!(ret, "{}", b).unwrap();
+        }
+    }
+    ret
+}
+
+fn string_param<'a>(h: &'a Helper) -> Result<&'a str, RenderError> {
+    h.param(0)
+        .and_then(|v| v.value().as_str())
+        .ok_or_else(|| RenderError::new(format!("{} expects a string", h.name())))
+}
+
+struct Literal(fn(&str) -> String);
+impl HelperDef for Literal {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        out.write(&(self.0)(string_param(h)?))?;
+        Ok(())
+    }
+}
+struct ByteArray;
+impl HelperDef for ByteArray {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let signed = h
+            .hash_get("signed")
+            .and_then(|v| v.value().as_bool())
+            .unwrap_or(false);
+        out.write(&bytes(string_param(h)?, signed))?;
+        Ok(())
+    }
+}
+
+pub(super) fn register(engine: &mut Handlebars) {
+    engine.register_helper("csharp_string", Box::new(Literal(csharp)));
+    engine.register_helper("java_string", Box::new(Literal(java)));
+    engine.register_helper("scala_string", Box::new(Literal(scala)));
+    engine.register_helper("python_string", Box::new(Literal(python)));
+    engine.register_helper("cpp_raw_string", Box::new(Literal(cpp_raw)));
+    engine.register_helper("byte_array", Box::new(ByteArray));
+}
@@ -1,9 +1,12 @@
 extern crate handlebars;
 extern crate serde;
 
-use super::error::{rander_error, template_error, Error, Result};
+use super::{
+    engine::engine,
+    error::{rander_error, Error, Result},
+};
 use crate::{
-    encoding::{traits, Template},
+    encoding::{alphabet::Alphabet, compress::Codec, traits, Template},
     judge::Verdict,
     random::random_standard,
     types::{DataId, TestMeta},
@@ -14,6 +17,7 @@ use std::str::SplitWhitespace;
 
 #[derive(Serialize)]
 struct MetaParam<'a, 'b> {
+    mode: &'static str,
     random: u64,
     ignore: &'b Vec<&'a DataId>,
 }
@@ -34,18 +38,37 @@ fn next_usize(split: &mut SplitWhitespace, name: &'static str) -> Result<usize>
         .map_err(|x| Error::ParseInt(name, x))
 }
 
-impl<'a> traits::MetaEncoding<'a, Error> for Meta<'a> {
-    fn new(template: &Template, max: usize) -> Result<Self> {
-        let mut ret = Meta {
+impl<'a> Meta<'a> {
+    pub fn new(template: &Template, max: usize) -> Result<Self> {
+        Ok(Meta {
             random: 0,
             ignore: Vec::with_capacity(max),
-            engine: Handlebars::new(),
-        };
-        ret.engine
-            .register_template_string("code", template.content.as_str())
-            .map_err(template_error)?;
-        Ok(ret)
+            engine: engine(template)?,
+        })
     }
+    pub fn decode(message: Verdict) -> Result<TestMeta> {
+        let mut p = message.output.split_whitespace();
+        Ok(TestMeta {
+            size: next_usize(&mut p, "size")?,
+            output_size: next_usize(&mut p, "output_size")?,
+            compress_size: next_usize(&mut p, "compress_size")?,
+            data_id: DataId {
+                hash: p.next().ok_or_else(|| split_error("hash"))?.to_string(),
+                answer: message.answer,
+            },
+            codec: match p.next() {
+                Some(v) => v.parse().map_err(Error::Codec)?,
+                None => Codec::default(),
+            },
+            alphabet: match p.next() {
+                Some(v) => v.parse().map_err(Error::Alphabet)?,
+                None => Alphabet::default(),
+            },
+            input: message.input,
+        })
+    }
+}
+impl<'a> traits::MetaEncoding<'a, Error> for Meta<'a> {
     fn init(&mut self) {
         self.random = random_standard();
     }
@@ -57,23 +80,11 @@ impl<'a> traits::MetaEncoding<'a, Error> for Meta<'a> {
             .render(
                 "code",
                 &MetaParam {
+                    mode: "meta",
                     random: self.random,
                     ignore: &self.ignore,
                 },
             )
             .map_err(rander_error)
     }
-    fn decode(message: Verdict) -> Result<TestMeta> {
-        let mut p = message.output.split_whitespace();
-        Ok(TestMeta {
-            size: next_usize(&mut p, "size")?,
-            output_size: next_usize(&mut p, "output_size")?,
-            compress_size: next_usize(&mut p, "compress_size")?,
-            data_id: DataId {
-                hash: p.next().ok_or_else(|| split_error("hash"))?.to_string(),
-                answer: message.answer,
-            },
-            input: message.input,
-        })
-    }
 }
@@ -0,0 +1,50 @@
+use super::{calibrate::Calibrate, encode::Encoder, meta::Meta};
+use crate::{
+    encoding::{
+        compress::Decoder,
+        scheme::{self, BoxDecoder, BoxEncoder, BoxMeta, BoxWriter, Dest, Erased},
+        stream, Calibration, Template,
+    },
+    judge::Verdict,
+    types::TestMeta,
+};
+
+/// Handlebars templates printing the compressed, text encoded test.
+pub struct Handlebars;
+
+impl scheme::Scheme for Handlebars {
+    fn name(&self) -> &str {
+        "handlebars"
+    }
+    fn meta<'a>(
+        &self,
+        template: &Template,
+        max_ignore: usize,
+    ) -> Result<BoxMeta<'a>, scheme::Error> {
+        Meta::new(template, max_ignore)
+            .map(|v| Box::new(Erased::wrap(v)) as BoxMeta<'a>)
+            .map_err(scheme::Error::new)
+    }
+    fn decode_meta(&self, message: Verdict) -> Result<TestMeta, scheme::Error> {
+        Meta::decode(message).map_err(scheme::Error::new)
+    }
+    fn encoder<'a>(
+        &self,
+        template: &Template,
+        max_ignore: usize,
+        length: usize,
+    ) -> Result<BoxEncoder<'a>, scheme::Error> {
+        Encoder::new(template, max_ignore, length)
+            .map(|v| Box::new(Erased::wrap(v)) as BoxEncoder<'a>)
+            .map_err(scheme::Error::new)
+    }
+    fn decoder(&self) -> BoxDecoder {
+        Box::new(Erased::wrap(Decoder::new()))
+    }
+    fn writer<'w>(&self, test: &TestMeta, dest: Dest<'w>) -> BoxWriter<'w> {
+        Box::new(Erased::wrap(stream::Decoder::new(test, dest)))
+    }
+    fn calibration(&self, template: &Template, length: usize) -> Result<String, scheme::Error> {
+        Calibrate::generate(template, length).map_err(scheme::Error::new)
+    }
+}
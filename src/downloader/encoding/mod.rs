@@ -1,16 +1,63 @@
-pub mod gzip;
+use crate::config::encoding::PARTIALS_DIR;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+pub mod alphabet;
+pub mod compress;
 pub mod handlebars {
+    pub mod calibrate;
     pub mod encode;
+    mod engine;
     mod error;
+    pub mod escape;
     pub mod meta;
+    pub mod scheme;
 
     pub use error::{Error, Result};
 }
+pub mod plugin;
+pub mod scheme;
+pub mod stream;
 mod traits;
 
-pub use traits::{DataDecoder, DataEncoder, MetaEncoding};
+pub use traits::{Calibration, DataDecoder, DataEncoder, MetaEncoding, StreamDecoder};
 
 pub struct Template {
     pub language: String,
     pub content: String,
+    /// Partials by name, shared between templates.
+    pub partials: HashMap<String, String>,
+}
+impl Template {
+    /// Read the template at `path`, with every file in the `partials`
+    /// directory next to it registered as a partial named by its file stem.
+    pub fn load(language: String, path: &Path) -> io::Result<Self> {
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+        let mut partials = HashMap::new();
+        let dir = path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(PARTIALS_DIR);
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if let (true, Some(name)) = (path.is_file(), path.file_stem()) {
+                    partials.insert(
+                        name.to_string_lossy().into_owned(),
+                        fs::read_to_string(&path)?,
+                    );
+                }
+            }
+        }
+        Ok(Template {
+            language,
+            content,
+            partials,
+        })
+    }
 }
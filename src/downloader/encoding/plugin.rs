@@ -0,0 +1,394 @@
+//! Encoding scheme implemented by an external executable.
+//!
+//! Every call runs the executable once: it gets a single JSON object on stdin
+//! and must print a single JSON object on stdout before exiting with status 0.
+//! Requests carry a `method` field:
+//!
+//! * `meta`: `template` (`language`, `content` and `partials`), `ignore`,
+//!   `random` → `source` of the meta program.
+//! * `decode_meta`: `verdict` of the meta program → `size`, `output_size`,
+//!   `compress_size` and `hash` of the test.
+//! * `data`: `template`, `ignore`, `random`, `length`, `offset` → `source`
+//!   printing `length` characters of the test starting at `offset`.
+//! * `decode`: `test` metadata and chunk `messages` → base64 encoded `data`.
+//! * `calibrate`: `template`, `random`, `length` → `source` printing `length`
+//!   characters.
+//!
+//! A reply of `{"error": "..."}` reports a failure.
+//!
+//! Encoders are synchronous, so every call blocks its thread until the
+//! executable exits. On a multi-threaded runtime the worker is handed over
+//! with `block_in_place` first, elsewhere the executable runs on a separate
+//! current-thread runtime.
+extern crate base64;
+extern crate serde;
+extern crate serde_json;
+extern crate tokio;
+
+use crate::{
+    encoding::{
+        scheme::{self, BoxDecoder, BoxEncoder, BoxMeta, BoxWriter, Dest},
+        DataDecoder, DataEncoder, MetaEncoding, StreamDecoder, Template,
+    },
+    judge::Verdict,
+    random::random_standard,
+    types::{DataId, TestMeta},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use std::{
+    error::Error as StdError,
+    fmt,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{ExitStatus, Output, Stdio},
+    result::Result as StdResult,
+    thread,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    process::Command,
+    runtime::{Builder, Handle, RuntimeFlavor},
+    task::block_in_place,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    Spawn(PathBuf, io::Error),
+    Io(io::Error),
+    Exit(ExitStatus),
+    Json(serde_json::Error),
+    Base64(base64::DecodeError),
+    Plugin(String),
+    Size(usize, usize),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Spawn(path, err) => write!(f, "Error running {}: {}", path.display(), err),
+            Error::Io(err) => write!(f, "Error talking to plugin: {}", err),
+            Error::Exit(status) => write!(f, "Plugin failed: {}", status),
+            Error::Json(err) => write!(f, "Invalid plugin message: {}", err),
+            Error::Base64(err) => write!(f, "Invalid plugin data: {}", err),
+            Error::Plugin(msg) => write!(f, "Plugin error: {}", msg),
+            Error::Size(expected, got) => {
+                write!(
+                    f,
+                    "Decoded size mismatch: expected {}, got {}",
+                    expected, got
+                )
+            }
+        }
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Spawn(_, err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Json(err) => Some(err),
+            Error::Base64(err) => Some(err),
+            Error::Exit(_) | Error::Plugin(_) | Error::Size(_, _) => None,
+        }
+    }
+}
+
+pub type Result<T> = StdResult<T, Error>;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Reply<T> {
+    Error { error: String },
+    Ok(T),
+}
+#[derive(Deserialize)]
+struct Source {
+    source: String,
+}
+#[derive(Deserialize)]
+struct Meta {
+    size: usize,
+    output_size: usize,
+    compress_size: usize,
+    hash: String,
+}
+#[derive(Deserialize)]
+struct Data {
+    data: String,
+}
+
+async fn run(path: &Path, request: &[u8]) -> Result<Output> {
+    let mut child = Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| Error::Spawn(path.to_path_buf(), e))?;
+    let mut stdin = child.stdin.take().unwrap();
+    // Write while reading the output, the plugin may reply before it read
+    // the whole request. Stdin is closed once written.
+    let (written, output) = tokio::join!(
+        async move { stdin.write_all(request).await },
+        child.wait_with_output()
+    );
+    let output = output.map_err(Error::Io)?;
+    if !output.status.success() {
+        return Err(Error::Exit(output.status));
+    }
+    written.map_err(Error::Io)?;
+    Ok(output)
+}
+/// Encoders are called synchronously, so this blocks the calling thread,
+/// but never a runtime worker that other tasks wait for.
+fn call<T: DeserializeOwned>(path: &Path, request: &Value) -> Result<T> {
+    let request = serde_json::to_vec(request).map_err(Error::Json)?;
+    let standalone = || {
+        Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(Error::Io)?
+            .block_on(run(path, &request))
+    };
+    let output = match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            block_in_place(|| handle.block_on(run(path, &request)))
+        }
+        // A single threaded runtime can't be blocked in place and can't
+        // start another runtime on its own thread.
+        Ok(_) => thread::scope(|s| s.spawn(standalone).join().unwrap()),
+        Err(_) => standalone(),
+    }?;
+    match serde_json::from_slice(&output.stdout).map_err(Error::Json)? {
+        Reply::Ok(v) => Ok(v),
+        Reply::Error { error } => Err(Error::Plugin(error)),
+    }
+}
+fn template(template: &Template) -> Value {
+    json!({
+        "language": template.language,
+        "content": template.content,
+        "partials": template.partials,
+    })
+}
+
+pub struct PluginMeta<'a> {
+    path: PathBuf,
+    template: Value,
+    random: u64,
+    ignore: Vec<&'a DataId>,
+}
+impl<'a> MetaEncoding<'a, Error> for PluginMeta<'a> {
+    fn init(&mut self) {
+        self.random = random_standard();
+    }
+    fn ignore<'b: 'a>(&mut self, hash: &'b DataId) {
+        self.ignore.push(hash);
+    }
+    fn generate(&self) -> Result<String> {
+        call(
+            &self.path,
+            &json!({
+                "method": "meta",
+                "template": self.template,
+                "ignore": self.ignore,
+                "random": self.random,
+            }),
+        )
+        .map(|v: Source| v.source)
+    }
+}
+
+pub struct PluginEncoder<'a> {
+    path: PathBuf,
+    template: Value,
+    random: u64,
+    length: usize,
+    ignore: Vec<&'a DataId>,
+}
+impl<'a> DataEncoder<'a, Error> for PluginEncoder<'a> {
+    fn init(&mut self) {
+        self.random = random_standard();
+    }
+    fn push_ignore<'b: 'a>(&mut self, hash: &'b DataId) {
+        self.ignore.push(hash);
+    }
+    fn pop_ignore(&mut self) {
+        self.ignore.pop();
+    }
+    fn generate(&self, offset: usize) -> Result<String> {
+        call(
+            &self.path,
+            &json!({
+                "method": "data",
+                "template": self.template,
+                "ignore": self.ignore,
+                "random": self.random,
+                "length": self.length,
+                "offset": offset,
+            }),
+        )
+        .map(|v: Source| v.source)
+    }
+}
+
+fn decode(path: &Path, test: &Value, size: usize, messages: &[String]) -> Result<Vec<u8>> {
+    let data: Data = call(
+        path,
+        &json!({
+            "method": "decode",
+            "test": test,
+            "messages": messages,
+        }),
+    )?;
+    let ret = base64::decode(data.data).map_err(Error::Base64)?;
+    if ret.len() != size {
+        return Err(Error::Size(size, ret.len()));
+    }
+    Ok(ret)
+}
+
+pub struct PluginDecoder {
+    path: PathBuf,
+    test: Value,
+    size: usize,
+    messages: Vec<String>,
+}
+impl DataDecoder for PluginDecoder {
+    type Error = Error;
+
+    fn init(&mut self, test: &TestMeta) {
+        self.test = json!(test);
+        self.size = test.size;
+    }
+    fn append_message(&mut self, message: &str) {
+        self.messages.push(message.to_string());
+    }
+    fn clear(&mut self) {
+        self.messages.clear();
+    }
+    fn decode(&mut self) -> Result<Vec<u8>> {
+        decode(&self.path, &self.test, self.size, &self.messages)
+    }
+}
+
+/// Collects the chunks of a test and writes it out once the plugin decoded it.
+pub struct PluginWriter<W: Write> {
+    path: PathBuf,
+    test: Value,
+    size: usize,
+    messages: Vec<String>,
+    dest: W,
+}
+impl<W: Write> StreamDecoder<W> for PluginWriter<W> {
+    type Error = Error;
+
+    fn append_message(&mut self, message: &str) -> Result<()> {
+        self.messages.push(message.to_string());
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<usize> {
+        let data = decode(&self.path, &self.test, self.size, &self.messages)?;
+        self.dest
+            .write_all(&data)
+            .and_then(|_| self.dest.flush())
+            .map_err(Error::Io)?;
+        Ok(data.len())
+    }
+}
+
+/// Scheme delegating to the executable at `path`.
+pub struct Plugin {
+    name: String,
+    path: PathBuf,
+}
+impl Plugin {
+    pub fn new(name: String, path: PathBuf) -> Self {
+        Plugin { name, path }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+impl scheme::Scheme for Plugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn meta<'a>(
+        &self,
+        template: &Template,
+        max_ignore: usize,
+    ) -> StdResult<BoxMeta<'a>, scheme::Error> {
+        Ok(Box::new(scheme::Erased::wrap(PluginMeta {
+            path: self.path.clone(),
+            template: self::template(template),
+            random: 0,
+            ignore: Vec::with_capacity(max_ignore),
+        })))
+    }
+    fn decode_meta(&self, message: Verdict) -> StdResult<TestMeta, scheme::Error> {
+        let meta: Meta = call(
+            &self.path,
+            &json!({
+                "method": "decode_meta",
+                "verdict": message,
+            }),
+        )
+        .map_err(scheme::Error::new)?;
+        Ok(TestMeta {
+            data_id: DataId {
+                hash: meta.hash,
+                answer: message.answer,
+            },
+            input: message.input,
+            size: meta.size,
+            output_size: meta.output_size,
+            compress_size: meta.compress_size,
+            codec: Default::default(),
+            alphabet: Default::default(),
+        })
+    }
+    fn encoder<'a>(
+        &self,
+        template: &Template,
+        max_ignore: usize,
+        length: usize,
+    ) -> StdResult<BoxEncoder<'a>, scheme::Error> {
+        Ok(Box::new(scheme::Erased::wrap(PluginEncoder {
+            path: self.path.clone(),
+            template: self::template(template),
+            random: 0,
+            length,
+            ignore: Vec::with_capacity(max_ignore),
+        })))
+    }
+    fn decoder(&self) -> BoxDecoder {
+        Box::new(scheme::Erased::wrap(PluginDecoder {
+            path: self.path.clone(),
+            test: Value::Null,
+            size: 0,
+            messages: Vec::new(),
+        }))
+    }
+    fn writer<'w>(&self, test: &TestMeta, dest: Dest<'w>) -> BoxWriter<'w> {
+        Box::new(scheme::Erased::wrap(PluginWriter {
+            path: self.path.clone(),
+            test: json!(test),
+            size: test.size,
+            messages: Vec::new(),
+            dest,
+        }))
+    }
+    fn calibration(&self, template: &Template, length: usize) -> StdResult<String, scheme::Error> {
+        call(
+            &self.path,
+            &json!({
+                "method": "calibrate",
+                "template": self::template(template),
+                "random": random_standard::<u64>(),
+                "length": length,
+            }),
+        )
+        .map(|v: Source| v.source)
+        .map_err(scheme::Error::new)
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    config::encoding::DEFAULT_SCHEME,
+    encoding::{
+        traits::{DataDecoder, DataEncoder, MetaEncoding, StreamDecoder},
+        Template,
+    },
+    error::Error as ErrType,
+    judge::Verdict,
+    types::{DataId, TestMeta},
+};
+use std::{
+    collections::HashMap, error::Error as StdError, fmt, io::Write, marker::PhantomData, sync::Arc,
+};
+
+/// Error of any scheme, displayed as the error it wraps.
+#[derive(Debug)]
+pub struct Error(Box<dyn StdError + Send + 'static>);
+impl Error {
+    pub fn new<E: ErrType>(error: E) -> Self {
+        Self(Box::new(error))
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Unknown(pub String);
+impl fmt::Display for Unknown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown encoding scheme {}", self.0)
+    }
+}
+impl StdError for Unknown {}
+
+pub type Dest<'w> = Box<dyn Write + Send + 'w>;
+pub type BoxMeta<'a> = Box<dyn MetaEncoding<'a, Error> + Send + 'a>;
+pub type BoxEncoder<'a> = Box<dyn DataEncoder<'a, Error> + Send + Sync + 'a>;
+pub type BoxDecoder = Box<dyn DataDecoder<Error = Error> + Send>;
+pub type BoxWriter<'w> = Box<dyn StreamDecoder<Dest<'w>, Error = Error> + Send + 'w>;
+
+/// A named set of encoders and decoders that work together: the data
+/// decoders must understand what the meta program reports.
+pub trait Scheme: Send + Sync {
+    fn name(&self) -> &str;
+    fn meta<'a>(&self, template: &Template, max_ignore: usize) -> Result<BoxMeta<'a>, Error>;
+    fn decode_meta(&self, message: Verdict) -> Result<TestMeta, Error>;
+    fn encoder<'a>(
+        &self,
+        template: &Template,
+        max_ignore: usize,
+        length: usize,
+    ) -> Result<BoxEncoder<'a>, Error>;
+    fn decoder(&self) -> BoxDecoder;
+    fn writer<'w>(&self, test: &TestMeta, dest: Dest<'w>) -> BoxWriter<'w>;
+    fn calibration(&self, template: &Template, length: usize) -> Result<String, Error>;
+}
+
+/// Adapter turning the errors of a typed encoder or decoder into `Error`.
+pub struct Erased<T, E>(T, PhantomData<fn() -> E>);
+impl<T, E> Erased<T, E> {
+    pub fn wrap(inner: T) -> Self {
+        Self(inner, PhantomData)
+    }
+}
+impl<'a, T, E> MetaEncoding<'a, Error> for Erased<T, E>
+where
+    T: MetaEncoding<'a, E>,
+    E: ErrType,
+{
+    fn init(&mut self) {
+        self.0.init()
+    }
+    fn ignore<'b: 'a>(&mut self, hash: &'b DataId) {
+        self.0.ignore(hash)
+    }
+    fn generate(&self) -> Result<String, Error> {
+        self.0.generate().map_err(Error::new)
+    }
+}
+impl<'a, T, E> DataEncoder<'a, Error> for Erased<T, E>
+where
+    T: DataEncoder<'a, E>,
+    E: ErrType,
+{
+    fn init(&mut self) {
+        self.0.init()
+    }
+    fn push_ignore<'b: 'a>(&mut self, hash: &'b DataId) {
+        self.0.push_ignore(hash)
+    }
+    fn pop_ignore(&mut self) {
+        self.0.pop_ignore()
+    }
+    fn generate(&self, offset: usize) -> Result<String, Error> {
+        self.0.generate(offset).map_err(Error::new)
+    }
+}
+impl<T: DataDecoder> DataDecoder for Erased<T, T::Error> {
+    type Error = Error;
+
+    fn init(&mut self, test: &TestMeta) {
+        self.0.init(test)
+    }
+    fn append_message(&mut self, message: &str) {
+        self.0.append_message(message)
+    }
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+    fn decode(&mut self) -> Result<Vec<u8>, Error> {
+        self.0.decode().map_err(Error::new)
+    }
+}
+impl<W: Write, T: StreamDecoder<W>> StreamDecoder<W> for Erased<T, T::Error> {
+    type Error = Error;
+
+    fn append_message(&mut self, message: &str) -> Result<(), Error> {
+        self.0.append_message(message).map_err(Error::new)
+    }
+    fn finish(&mut self) -> Result<usize, Error> {
+        self.0.finish().map_err(Error::new)
+    }
+}
+
+pub struct Registry {
+    schemes: HashMap<String, Arc<dyn Scheme>>,
+    default: String,
+}
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            schemes: HashMap::new(),
+            default: DEFAULT_SCHEME.to_string(),
+        }
+    }
+    pub fn register(&mut self, scheme: Arc<dyn Scheme>) {
+        self.schemes.insert(scheme.name().to_string(), scheme);
+    }
+    pub fn default_scheme(&self) -> &str {
+        &self.default
+    }
+    pub fn set_default(&mut self, name: &str) -> Result<(), Unknown> {
+        if !self.schemes.contains_key(name) {
+            return Err(Unknown(name.to_string()));
+        }
+        self.default = name.to_string();
+        Ok(())
+    }
+    /// Look up `name`, or the default scheme if none is given.
+    pub fn get(&self, name: Option<&str>) -> Result<Arc<dyn Scheme>, Unknown> {
+        let name = name.unwrap_or(&self.default);
+        self.schemes
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Unknown(name.to_string()))
+    }
+    pub fn names(&self) -> Vec<&str> {
+        let mut ret: Vec<&str> = self.schemes.keys().map(String::as_str).collect();
+        ret.sort_unstable();
+        ret
+    }
+}
+impl Default for Registry {
+    fn default() -> Self {
+        let mut ret = Self::new();
+        ret.register(Arc::new(super::handlebars::scheme::Handlebars));
+        ret
+    }
+}
@@ -0,0 +1,142 @@
+extern crate brotli_decompressor;
+extern crate flate2;
+
+use crate::{
+    encoding::{
+        alphabet::Alphabet,
+        compress::{Codec, Counter, Error, BROTLI_BUFFER},
+        traits::StreamDecoder,
+    },
+    types::TestMeta,
+};
+use brotli_decompressor::DecompressorWriter;
+use flate2::write::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use std::{
+    cmp::min,
+    io::{self, Write},
+    vec::Vec,
+};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Decompressor fed with compressed bytes as they are decoded. Xz and zstd
+/// have no push based decoder, so their compressed data is kept until the end.
+enum Sink<W: Write> {
+    Gzip(GzDecoder<Counter<W>>),
+    Zlib(ZlibDecoder<Counter<W>>),
+    Deflate(DeflateDecoder<Counter<W>>),
+    Brotli(Box<DecompressorWriter<Counter<W>>>),
+    Buffered(Codec, Vec<u8>, Counter<W>),
+}
+impl<W: Write> Sink<W> {
+    fn new(codec: Codec, dest: W) -> Self {
+        let dest = Counter::new(dest);
+        match codec {
+            Codec::Gzip => Self::Gzip(GzDecoder::new(dest)),
+            Codec::Zlib => Self::Zlib(ZlibDecoder::new(dest)),
+            Codec::Deflate => Self::Deflate(DeflateDecoder::new(dest)),
+            Codec::Brotli => Self::Brotli(Box::new(DecompressorWriter::new(dest, BROTLI_BUFFER))),
+            Codec::Xz | Codec::Zstd => Self::Buffered(codec, Vec::new(), dest),
+        }
+    }
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Gzip(w) => w.write_all(data),
+            Self::Zlib(w) => w.write_all(data),
+            Self::Deflate(w) => w.write_all(data),
+            Self::Brotli(w) => w.write_all(data),
+            Self::Buffered(_, buf, _) => {
+                buf.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+    fn finish(self) -> io::Result<Counter<W>> {
+        let mut ret = match self {
+            Self::Gzip(w) => w.finish()?,
+            Self::Zlib(w) => w.finish()?,
+            Self::Deflate(w) => w.finish()?,
+            Self::Brotli(mut w) => {
+                w.close()?;
+                w.into_inner().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "incomplete brotli stream")
+                })?
+            }
+            Self::Buffered(codec, buf, mut dest) => {
+                codec.decompress(buf.as_slice(), &mut dest)?;
+                dest
+            }
+        };
+        ret.flush()?;
+        Ok(ret)
+    }
+}
+
+/// Decoder writing the test to `W` while chunks arrive, only the symbols of an
+/// unfinished group are kept between messages.
+pub struct Decoder<W: Write> {
+    pending: Vec<u8>,
+    decoded: Vec<u8>,
+    size: usize,
+    remain: usize,
+    codec: Codec,
+    alphabet: Alphabet,
+    sink: Option<Sink<W>>,
+}
+impl<W: Write> Decoder<W> {
+    pub fn new(test: &TestMeta, dest: W) -> Self {
+        Decoder {
+            pending: Vec::new(),
+            decoded: Vec::new(),
+            size: test.size,
+            remain: test.compress_size,
+            codec: test.codec,
+            alphabet: test.alphabet,
+            sink: Some(Sink::new(test.codec, dest)),
+        }
+    }
+    fn push(&mut self, len: usize) -> Result<()> {
+        self.alphabet
+            .decode(&self.pending[..len], &mut self.decoded)
+            .map_err(Error::Decode)?;
+        self.pending.drain(..len);
+        let take = min(self.remain, self.decoded.len());
+        self.remain -= take;
+        let ret = match self.sink.as_mut() {
+            Some(sink) => sink.write(&self.decoded[..take]),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "decoder finished",
+            )),
+        }
+        .map_err(|e| Error::Decompress(self.codec, e));
+        self.decoded.clear();
+        ret
+    }
+}
+
+impl<W: Write> StreamDecoder<W> for Decoder<W> {
+    type Error = Error;
+
+    fn append_message(&mut self, message: &str) -> Result<()> {
+        self.pending
+            .extend_from_slice(self.alphabet.trim(message).as_bytes());
+        self.push(self.alphabet.whole_groups(self.pending.len()))
+    }
+    fn finish(&mut self) -> Result<usize> {
+        self.push(self.pending.len())?;
+        let written = match self.sink.take() {
+            Some(sink) => sink.finish(),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "decoder finished",
+            )),
+        }
+        .map_err(|e| Error::Decompress(self.codec, e))?
+        .count();
+        if written != self.size {
+            return Err(Error::Size(self.size, written));
+        }
+        Ok(written)
+    }
+}
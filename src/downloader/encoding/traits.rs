@@ -1,34 +1,44 @@
 use crate::{
     encoding::Template,
     error::Error,
-    judge::Verdict,
     types::{DataId, TestMeta},
 };
+use std::io::Write;
 
-pub trait MetaEncoding<'a, Err: Error>: Sized {
-    fn new(template: &Template, max_ignore: usize) -> Result<Self, Err>;
+pub trait MetaEncoding<'a, Err: Error> {
     fn init(&mut self);
     fn ignore<'b: 'a>(&mut self, hash: &'b DataId);
     fn generate(&self) -> Result<String, Err>;
-    fn decode(message: Verdict) -> Result<TestMeta, Err>;
 }
 
-pub trait DataEncoder<'a, Err: Error>: Sized {
-    fn new(template: &Template, max_ignore: usize) -> Result<Self, Err>;
+pub trait DataEncoder<'a, Err: Error> {
     fn init(&mut self);
     fn push_ignore<'b: 'a>(&mut self, hash: &'b DataId);
     fn pop_ignore(&mut self);
     fn generate(&self, offset: usize) -> Result<String, Err>;
 }
 
-pub trait DataDecoder: Sized
+pub trait Calibration<Err: Error> {
+    fn generate(template: &Template, length: usize) -> Result<String, Err>;
+}
+
+pub trait DataDecoder
 where
     Self::Error: Error,
 {
     type Error;
-    fn new() -> Self;
     fn init(&mut self, test: &TestMeta);
     fn append_message(&mut self, message: &str);
     fn clear(&mut self);
-    fn decode(&mut self) -> Result<String, Self::Error>;
+    fn decode(&mut self) -> Result<Vec<u8>, Self::Error>;
+}
+
+pub trait StreamDecoder<W: Write>
+where
+    Self::Error: Error,
+{
+    type Error;
+    fn append_message(&mut self, message: &str) -> Result<(), Self::Error>;
+    /// Flush the test to the writer, the decoder can't be used afterwards.
+    fn finish(&mut self) -> Result<usize, Self::Error>;
 }
@@ -0,0 +1,315 @@
+extern crate futures;
+extern crate serde;
+extern crate serde_yaml;
+extern crate tokio;
+
+pub mod state;
+
+use crate::{
+    cache::storage::StoageError,
+    downloader::{data, meta_storage, Downloader},
+    encoding::{
+        scheme::{Registry, Unknown},
+        Template,
+    },
+    judge::problem::{Problem, Type},
+    submitter::Submitter,
+};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use state::State;
+use std::{
+    error::Error as StdError,
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::task::{spawn, JoinError};
+
+pub use data::DataError;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Meta(Box<meta_storage::Error>),
+    Cache(Box<StoageError>),
+    Range(usize, usize, usize),
+    Scheme(Unknown),
+    Data(DataError),
+    Join(JoinError),
+    State(serde_yaml::Error),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "Error accessing {}: {}", path.display(), err),
+            Self::Meta(err) => write!(f, "Error loading metadata: {}", err),
+            Self::Cache(err) => write!(f, "Error processing cache: {}", err),
+            Self::Range(begin, end, len) => write!(
+                f,
+                "Range [{}, {}) out of range. Only {} tests in metadata",
+                begin, end, len
+            ),
+            Self::Scheme(err) => write!(f, "{}", err),
+            Self::Data(err) => write!(f, "{}", err),
+            Self::Join(err) => write!(f, "Error joining task: {}", err),
+            Self::State(err) => write!(f, "Error processing job state: {}", err),
+        }
+    }
+}
+impl Error {
+    fn meta(err: meta_storage::Error) -> Self {
+        Self::Meta(Box::new(err))
+    }
+    fn cache(err: StoageError) -> Self {
+        Self::Cache(Box::new(err))
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Meta(err) => Some(&**err),
+            Self::Cache(err) => Some(&**err),
+            Self::Range(_, _, _) => None,
+            Self::Scheme(err) => Some(err),
+            Self::Data(err) => Some(err),
+            Self::Join(err) => Some(err),
+            Self::State(err) => Some(err),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TemplateFile {
+    pub language: String,
+    pub path: PathBuf,
+}
+impl TemplateFile {
+    pub fn load(&self) -> Result<Template, Error> {
+        Template::load(self.language.clone(), &self.path)
+            .map_err(|e| Error::Io(self.path.clone(), e))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Job {
+    #[serde(default)]
+    pub source: Type,
+    pub contest: String,
+    pub id: String,
+    pub template: TemplateFile,
+    #[serde(default)]
+    pub scheme: Option<String>,
+    pub meta: PathBuf,
+    pub cache: Option<PathBuf>,
+    #[serde(default)]
+    pub state: Option<PathBuf>,
+    pub begin: usize,
+    pub end: Option<usize>,
+    pub output: PathBuf,
+}
+pub struct Report {
+    pub problem: Problem,
+    pub skipped: usize,
+    pub tests: Vec<(usize, Result<(), Error>)>,
+    pub stopped: Option<data::BudgetError>,
+}
+
+pub fn from_reader<R: Read>(rdr: R) -> Result<Vec<Job>, serde_yaml::Error> {
+    serde_yaml::from_reader(rdr)
+}
+
+fn open(path: &Path) -> Result<File, Error> {
+    File::open(path).map_err(|e| Error::Io(path.to_path_buf(), e))
+}
+fn save_with<F>(path: &Path, fun: F) -> Result<(), Error>
+where
+    F: FnOnce(File) -> Result<(), Error>,
+{
+    let tmp = path.with_extension("tmp");
+    fun(File::create(&tmp).map_err(|e| Error::Io(tmp.clone(), e))?)?;
+    fs::rename(&tmp, path).map_err(|e| Error::Io(path.to_path_buf(), e))
+}
+
+impl Job {
+    pub fn problem(&self) -> Problem {
+        Problem::new(self.source.clone(), self.contest.clone(), self.id.clone())
+    }
+    fn cache_path(&self) -> PathBuf {
+        self.cache
+            .clone()
+            .unwrap_or_else(|| self.output.join("cache.yaml"))
+    }
+    fn state_path(&self) -> PathBuf {
+        self.state
+            .clone()
+            .unwrap_or_else(|| self.output.join("state.yaml"))
+    }
+    pub async fn run(
+        self,
+        submitter: Arc<Submitter>,
+        registry: Arc<Registry>,
+    ) -> Result<Report, Error> {
+        self.execute(submitter, registry, State::default()).await
+    }
+    pub async fn resume(
+        self,
+        submitter: Arc<Submitter>,
+        registry: Arc<Registry>,
+    ) -> Result<Report, Error> {
+        let path = self.state_path();
+        let state = if path.exists() {
+            State::load(&path)?
+        } else {
+            State::default()
+        };
+        self.execute(submitter, registry, state).await
+    }
+
+    async fn execute(
+        self,
+        submitter: Arc<Submitter>,
+        registry: Arc<Registry>,
+        mut state: State,
+    ) -> Result<Report, Error> {
+        let problem = self.problem();
+        let cache_path = self.cache_path();
+        let state_path = self.state_path();
+        let mut downloader = Downloader::new(problem.clone(), submitter);
+        downloader
+            .load_meta(open(&self.meta)?)
+            .map_err(Error::meta)?;
+        if cache_path.exists() {
+            downloader
+                .cache
+                .load(open(&cache_path)?)
+                .map_err(Error::cache)?;
+        }
+        let scheme = registry
+            .get(self.scheme.as_deref().or_else(|| downloader.scheme()))
+            .map_err(Error::Scheme)?;
+        let end = self.end.unwrap_or_else(|| downloader.len());
+        if self.begin >= end || end > downloader.len() {
+            return Err(Error::Range(self.begin, end, downloader.len()));
+        }
+        let template = self.template.load()?;
+        fs::create_dir_all(&self.output).map_err(|e| Error::Io(self.output.clone(), e))?;
+
+        let mut report = Report {
+            problem,
+            skipped: 0,
+            tests: Vec::new(),
+            stopped: None,
+        };
+        // Submit every missing test up front, one run of consecutive tests
+        // at a time, so that all of them are judged in parallel.
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for index in self.begin..end {
+            if state.written.contains(&index) {
+                report.skipped += 1;
+            } else {
+                match runs.last_mut() {
+                    Some((_, e)) if *e == index => *e += 1,
+                    _ => runs.push((index, index + 1)),
+                }
+            }
+        }
+        let mut runs = runs.into_iter();
+        let mut submitted: Option<data::Submitted<_>> = None;
+        for (begin, end) in runs.by_ref() {
+            let run = downloader
+                .submit_data(&*scheme, &template, begin, end)
+                .await
+                .map_err(|e| Error::Data(data::Error::Build(e)))?;
+            let stopped = run.budget().is_some();
+            match &mut submitted {
+                Some(v) => v.append(run),
+                None => submitted = Some(run),
+            }
+            save_with(&cache_path, |f| {
+                downloader.cache.save(f).map_err(Error::cache)
+            })?;
+            if stopped {
+                break;
+            }
+        }
+        let submitted = match submitted {
+            Some(v) => v,
+            None => return Ok(report),
+        };
+        if let Some(mut stopped) = submitted.budget().cloned() {
+            for (begin, end) in runs {
+                for i in begin..end {
+                    stopped.remaining.extend(downloader.chunks(i));
+                }
+            }
+            report.stopped = Some(stopped);
+        }
+        // Tests missing chunks because of the budget are left for a resume.
+        let unfinished: Vec<usize> = report
+            .stopped
+            .iter()
+            .flat_map(|v| v.remaining.iter().map(|k| k.test - 1))
+            .collect();
+
+        let output = &self.output;
+        downloader
+            .save_each(
+                &*scheme,
+                submitted,
+                |index| File::create(output.join(format!("{}.in", index))).map(BufWriter::new),
+                |cache, index, result| {
+                    save_with(&cache_path, |f| cache.save(f).map_err(Error::cache))?;
+                    if result.is_ok() {
+                        state.written.push(index);
+                        state.save(&state_path)?;
+                    } else {
+                        let _ = fs::remove_file(output.join(format!("{}.in", index)));
+                    }
+                    if !unfinished.contains(&index) {
+                        report
+                            .tests
+                            .push((index, result.map(|_| ()).map_err(Error::Data)));
+                    }
+                    Ok(())
+                },
+            )
+            .await?;
+        save_with(&cache_path, |f| {
+            downloader.cache.save(f).map_err(Error::cache)
+        })?;
+        Ok(report)
+    }
+}
+
+pub async fn run_all<It: IntoIterator<Item = Job>>(
+    jobs: It,
+    submitter: Arc<Submitter>,
+    registry: Arc<Registry>,
+) -> Vec<Result<Report, Error>> {
+    join_all(
+        jobs.into_iter()
+            .map(|job| spawn(job.run(submitter.clone(), registry.clone()))),
+    )
+    .await
+    .into_iter()
+    .map(|x| x.map_err(Error::Join).and_then(|x| x))
+    .collect()
+}
+pub async fn resume_all<It: IntoIterator<Item = Job>>(
+    jobs: It,
+    submitter: Arc<Submitter>,
+    registry: Arc<Registry>,
+) -> Vec<Result<Report, Error>> {
+    join_all(
+        jobs.into_iter()
+            .map(|job| spawn(job.resume(submitter.clone(), registry.clone()))),
+    )
+    .await
+    .into_iter()
+    .map(|x| x.map_err(Error::Join).and_then(|x| x))
+    .collect()
+}
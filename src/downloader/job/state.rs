@@ -0,0 +1,23 @@
+extern crate serde;
+extern crate serde_yaml;
+
+use super::{save_with, Error};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, path::Path};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct State {
+    pub written: Vec<usize>,
+}
+
+impl State {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        serde_yaml::from_reader(File::open(path).map_err(|e| Error::Io(path.to_path_buf(), e))?)
+            .map_err(Error::State)
+    }
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        save_with(path, |f| {
+            serde_yaml::to_writer(f, self).map_err(Error::State)
+        })
+    }
+}
@@ -1,9 +1,12 @@
 extern crate reqwest;
 
+use std::sync::{atomic::AtomicBool, Arc};
+
+pub mod contest;
 mod error;
 pub mod problem;
 pub mod register;
-mod retry;
+pub mod retry;
 mod search;
 pub mod session;
 pub mod submit;
@@ -27,8 +30,10 @@ impl UtilityRegex {
 
 pub struct Session {
     client: reqwest::Client,
+    jar: Arc<session::Cookies>,
     pub handle: String,
-    pub online: bool,
+    online: AtomicBool,
     ftaa: String,
     regex: UtilityRegex,
+    retry: retry::RetryPolicy,
 }
@@ -0,0 +1,94 @@
+extern crate reqwest;
+extern crate serde;
+
+use super::{
+    error::{network_error, Error, Kind, Result},
+    retry::{async_retry, RetryPolicy},
+};
+use crate::config::judge::contest::API_URL;
+use reqwest::Client;
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Phase {
+    Before,
+    Coding,
+    PendingSystemTest,
+    SystemTest,
+    Finished,
+}
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Before => "not started",
+            Self::Coding => "running",
+            Self::PendingSystemTest => "pending system test",
+            Self::SystemTest => "system testing",
+            Self::Finished => "finished",
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Contest {
+    pub id: u64,
+    pub name: String,
+    pub phase: Phase,
+}
+
+#[derive(Deserialize)]
+struct Standings {
+    contest: Contest,
+}
+#[derive(Deserialize)]
+struct Response<T> {
+    status: String,
+    comment: Option<String>,
+    result: Option<T>,
+}
+
+pub struct ContestApi {
+    client: Client,
+    base: String,
+    retry: RetryPolicy,
+}
+impl Default for ContestApi {
+    fn default() -> Self {
+        Self::new(API_URL)
+    }
+}
+impl ContestApi {
+    pub fn new<T: Into<String>>(base: T) -> Self {
+        Self {
+            client: Client::new(),
+            base: base.into(),
+            retry: RetryPolicy::default(),
+        }
+    }
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+    pub async fn contest(&self, contest: &str) -> Result<Contest> {
+        let url = format!("{}/contest.standings", self.base);
+        let response = async_retry(&self.retry, || async {
+            self.client
+                .get(&url)
+                .query(&[("contestId", contest), ("from", "1"), ("count", "1")])
+                .send()
+                .await?
+                .json::<Response<Standings>>()
+                .await
+        })
+        .await
+        .map_err(network_error)?;
+        match response.result {
+            Some(s) if response.status == "OK" => Ok(s.contest),
+            _ => Err(Error::with_description(
+                Kind::Api,
+                response.comment.unwrap_or(response.status),
+            )),
+        }
+    }
+}
@@ -1,8 +1,14 @@
 extern crate reqwest;
 
+use super::{
+    retry::{classify, ErrorClass},
+    submit::Rejection,
+};
 use crate::email;
 use std::{boxed::Box, convert::Into, error::Error as StdError, fmt, result::Result as StdResult};
 
+const THROTTLE_MESSAGE: [&str; 3] = ["too many", "too often", "try again later"];
+
 #[derive(Debug)]
 pub(super) enum Kind {
     Builder(reqwest::Error),
@@ -12,6 +18,9 @@ pub(super) enum Kind {
     Regex,
     Email(email::Error),
     TestCount(usize, usize),
+    Detached,
+    LoggedOut,
+    Rejected(Rejection),
 }
 #[derive(Debug)]
 pub struct Error {
@@ -39,6 +48,12 @@ impl fmt::Display for Error {
             Kind::TestCount(count, expect) => {
                 write!(f, "Test count not match. Expected {} got {}", expect, count)
             }
+            Kind::Detached => write!(f, "Submission isn't attached to a session"),
+            Kind::LoggedOut => write!(f, "Session expired"),
+            Kind::Rejected(r) => {
+                write!(f, "Submission rejected ({})", r)?;
+                self.write_description(f)
+            }
         }
     }
 }
@@ -48,11 +63,46 @@ impl StdError for Error {
             Kind::Builder(x) | Kind::Network(x) => Some(x),
             Kind::Csrf(x) => Some(x.as_ref()),
             Kind::Email(e) => Some(e),
-            Kind::Api | Kind::Regex | Kind::TestCount(_, _) => None,
+            Kind::Api
+            | Kind::Regex
+            | Kind::TestCount(_, _)
+            | Kind::Detached
+            | Kind::LoggedOut
+            | Kind::Rejected(_) => None,
         }
     }
 }
 impl Error {
+    pub fn is_logged_out(&self) -> bool {
+        matches!(self.kind, Kind::LoggedOut)
+    }
+    pub fn rejection(&self) -> Option<Rejection> {
+        match &self.kind {
+            Kind::Rejected(r) => Some(*r),
+            _ => None,
+        }
+    }
+    pub fn class(&self) -> ErrorClass {
+        match &self.kind {
+            Kind::Network(e) => classify(e),
+            Kind::Csrf(e) => e.class(),
+            Kind::Api => match &self.description {
+                Some(d)
+                    if THROTTLE_MESSAGE
+                        .iter()
+                        .any(|x| d.to_lowercase().contains(x)) =>
+                {
+                    ErrorClass::Throttled
+                }
+                _ => ErrorClass::Permanent,
+            },
+            Kind::Regex | Kind::Email(_) | Kind::LoggedOut => ErrorClass::Transient,
+            Kind::Rejected(Rejection::Limit) => ErrorClass::Throttled,
+            Kind::Builder(_) | Kind::TestCount(_, _) | Kind::Detached | Kind::Rejected(_) => {
+                ErrorClass::Permanent
+            }
+        }
+    }
     pub(super) fn with_kind(kind: Kind) -> Self {
         Self {
             kind,
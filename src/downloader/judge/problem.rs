@@ -7,11 +7,20 @@ use super::{
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub enum Type {
+    #[default]
     Contest,
     Gym,
 }
+impl Type {
+    fn path(&self) -> &'static str {
+        match self {
+            Self::Contest => "contest",
+            Self::Gym => "gym",
+        }
+    }
+}
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Problem {
     pub(super) submit_url: String,
@@ -28,8 +37,16 @@ impl PartialEq for Problem {
 impl Problem {
     pub fn new(source: Type, contest: String, id: String) -> Self {
         Problem {
-            submit_url: format!("https://codeforces.com/contest/{}/submit", contest),
-            status_url: format!("https://codeforces.com/contest/{}/status", contest),
+            submit_url: format!(
+                "https://codeforces.com/{}/{}/submit",
+                source.path(),
+                contest
+            ),
+            status_url: format!(
+                "https://codeforces.com/{}/{}/status",
+                source.path(),
+                contest
+            ),
             source,
             contest,
             id,
@@ -38,12 +55,20 @@ impl Problem {
 }
 impl fmt::Display for Problem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Contest-{}{}", self.contest, self.id)
+        match self.source {
+            Type::Contest => write!(f, "Contest-{}{}", self.contest, self.id),
+            Type::Gym => write!(f, "Gym-{}{}", self.contest, self.id),
+        }
     }
 }
 
-fn get_problem_url(_source: Type, contest: &str, id: &str) -> String {
-    format!("https://codeforces.com/contest/{}/problem/{}", contest, id)
+fn get_problem_url(source: Type, contest: &str, id: &str) -> String {
+    format!(
+        "https://codeforces.com/{}/{}/problem/{}",
+        source.path(),
+        contest,
+        id
+    )
 }
 
 impl Session {
@@ -46,7 +46,7 @@ impl RegexSet {
 
 impl Session {
     async fn post_empty(&self, ftaa: &str, csrf: &str) -> Result<()> {
-        async_retry(async || {
+        async_retry(&self.retry, async || {
             self.client
                 .post("https://codeforces.com/data/empty")
                 .form(&[("bfaa", BFAA), ("ftaa", ftaa), ("csrf_token", csrf)])
@@ -61,7 +61,7 @@ impl Session {
     pub async fn register(&mut self, password: &str, email: &Email) -> Result<()> {
         let regex = RegexSet::new();
         const URL: &str = "https://codeforces.com/register";
-        let body: String = async_retry(async || {
+        let body: String = async_retry(&self.retry, async || {
             self.client
                 .get(URL)
                 .send()
@@ -74,7 +74,7 @@ impl Session {
         .map_err(network_error)?;
         let csrf = self.find_csrf(&body)?;
         let name = regex.find_name(&body)?;
-        async_retry(async || {
+        async_retry(&self.retry, async || {
             self.client
                 .post(URL)
                 .form(&[("action", "welcome"), ("csrf_token", csrf.as_str())])
@@ -89,7 +89,7 @@ impl Session {
 
         regex
             .find_error(
-                &async_retry(async || {
+                &async_retry(&self.retry, async || {
                     self.client
                         .post(URL)
                         .form(&[
@@ -121,10 +121,12 @@ impl Session {
             .map_err(|x| Error::with_kind(Kind::Email(x)))?
         {
             if p.contains("register") {
-                async_retry(async || self.client.get(p.as_str()).send().await?.error_for_status())
-                    .await
-                    .map_err(network_error)?;
-                self.online = true;
+                async_retry(&self.retry, async || {
+                    self.client.get(p.as_str()).send().await?.error_for_status()
+                })
+                .await
+                .map_err(network_error)?;
+                self.set_online(true);
                 return Ok(());
             }
         }
@@ -1,27 +1,87 @@
 extern crate reqwest;
 extern crate tokio;
 
-use crate::config::retry::{FORBIDDEN_DELAY, RETRY_COUNT, RETRY_DELAY};
-use reqwest::Result;
-use std::future::Future;
+use crate::{
+    config::retry::{
+        FORBIDDEN_DELAY, FORBIDDEN_MAX_DELAY, RETRY_COUNT, RETRY_DELAY, RETRY_JITTER,
+        RETRY_MAX_DELAY,
+    },
+    random::random_standard,
+};
+use reqwest::{Result, StatusCode};
+use std::{future::Future, time::Duration};
 use tokio::time::sleep;
 
-pub(super) async fn async_retry<'a, F, U, Out>(fun: F) -> Result<Out>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Transient,
+    Throttled,
+    Permanent,
+}
+pub fn classify(err: &reqwest::Error) -> ErrorClass {
+    match err.status() {
+        Some(StatusCode::FORBIDDEN) | Some(StatusCode::TOO_MANY_REQUESTS) => ErrorClass::Throttled,
+        Some(s) if s.is_client_error() => ErrorClass::Permanent,
+        Some(_) => ErrorClass::Transient,
+        None if err.is_builder() || err.is_redirect() => ErrorClass::Permanent,
+        None => ErrorClass::Transient,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub count: u32,
+    pub delay: Duration,
+    pub max_delay: Duration,
+    pub throttle_delay: Duration,
+    pub max_throttle_delay: Duration,
+    pub jitter: f64,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            count: RETRY_COUNT,
+            delay: RETRY_DELAY,
+            max_delay: RETRY_MAX_DELAY,
+            throttle_delay: FORBIDDEN_DELAY,
+            max_throttle_delay: FORBIDDEN_MAX_DELAY,
+            jitter: RETRY_JITTER,
+        }
+    }
+}
+impl RetryPolicy {
+    pub fn delay(&self, attempt: u32, class: ErrorClass) -> Option<Duration> {
+        let (base, max) = match class {
+            ErrorClass::Transient => (self.delay, self.max_delay),
+            ErrorClass::Throttled => (self.throttle_delay, self.max_throttle_delay),
+            ErrorClass::Permanent => return None,
+        };
+        let delay = base
+            .checked_mul(1 << attempt.min(16))
+            .map_or(max, |d| d.min(max));
+        Some(delay.mul_f64(1.0 - self.jitter * random_standard::<f64>()))
+    }
+}
+
+pub(super) async fn async_retry<F, U, Out>(policy: &RetryPolicy, fun: F) -> Result<Out>
 where
     F: Fn() -> U,
     U: Future<Output = reqwest::Result<Out>>,
 {
-    for _i in 0..RETRY_COUNT - 1 {
+    let mut attempt = 0;
+    loop {
         match fun().await {
             Ok(v) => return Ok(v),
             Err(e) => {
-                if e.status() == Some(reqwest::StatusCode::FORBIDDEN) {
-                    sleep(FORBIDDEN_DELAY).await;
-                } else {
-                    sleep(RETRY_DELAY).await;
+                if attempt + 1 >= policy.count {
+                    return Err(e);
+                }
+                match policy.delay(attempt, classify(&e)) {
+                    Some(d) => sleep(d).await,
+                    None => return Err(e),
                 }
             }
         }
+        attempt += 1;
     }
-    fun().await
 }
@@ -1,7 +1,7 @@
 extern crate regex;
 extern crate reqwest;
 
-use super::retry::async_retry;
+use super::retry::{async_retry, RetryPolicy};
 use regex::Regex;
 use reqwest::{RequestBuilder, Result};
 
@@ -12,11 +12,15 @@ pub fn search_text(text: &str, regex: &Regex) -> Option<String> {
 }
 
 pub async fn search_response<T: Fn() -> RequestBuilder>(
+    policy: &RetryPolicy,
     fun: T,
     regex: &Regex,
 ) -> Result<Option<String>> {
     Ok(search_text(
-        &async_retry(async || fun().send().await?.error_for_status()?.text().await).await?,
+        &async_retry(policy, async || {
+            fun().send().await?.error_for_status()?.text().await
+        })
+        .await?,
         regex,
     ))
 }
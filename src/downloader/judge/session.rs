@@ -1,9 +1,11 @@
+extern crate cookie_store;
 extern crate regex;
 extern crate reqwest;
+extern crate serde;
 
 use super::{
     error::{network_error, regex_mismatch, Error, Kind, Result},
-    retry::async_retry,
+    retry::{async_retry, RetryPolicy},
     search::{search_response, search_text},
     Session, UtilityRegex,
 };
@@ -11,11 +13,58 @@ use crate::{
     config::judge::session::{BFAA, VERBOSE},
     random::random_hex,
 };
+use cookie_store::{Cookie, RawCookie};
 use regex::Regex;
-use reqwest::{Client, ClientBuilder, Proxy};
-use std::boxed::Box;
+use reqwest::{cookie::CookieStore, header::HeaderValue, Client, ClientBuilder, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::{
+    boxed::Box,
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
 
 const FIREFOX_UA: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:78.0) Gecko/20100101 Firefox/78.0";
+const HOME_URL: &str = "https://codeforces.com/";
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedSession {
+    pub handle: String,
+    ftaa: String,
+    cookies: Vec<Cookie<'static>>,
+}
+
+/// Cookie provider of a session. Unlike `reqwest::cookie::Jar` it exposes
+/// the stored cookies, so that a session is saved with the domain, path,
+/// expiry and flags of each one.
+#[derive(Default)]
+pub(super) struct Cookies(RwLock<cookie_store::CookieStore>);
+impl CookieStore for Cookies {
+    fn set_cookies(&self, headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = headers
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|v| RawCookie::parse(v).ok())
+            .map(RawCookie::into_owned);
+        self.0.write().unwrap().store_response_cookies(cookies, url);
+    }
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let value = self
+            .0
+            .read()
+            .unwrap()
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if value.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(value.as_str()).ok()
+        }
+    }
+}
 
 pub(super) struct RegexSet {
     csrf: Regex,
@@ -38,22 +87,34 @@ fn csrf_network_error(error: reqwest::Error) -> Error {
 
 impl Session {
     fn from_client(builder: ClientBuilder) -> Self {
+        let jar = Arc::new(Cookies::default());
         Session {
             client: builder
                 .user_agent(FIREFOX_UA)
-                .cookie_store(true)
+                .cookie_provider(jar.clone())
                 .connection_verbose(VERBOSE)
                 .build()
                 .unwrap(),
+            jar,
             handle: String::new(),
-            online: false,
+            online: AtomicBool::new(false),
             ftaa: random_hex(18),
             regex: UtilityRegex::new(),
+            retry: RetryPolicy::default(),
         }
     }
     pub fn new() -> Self {
         Self::from_client(Client::builder())
     }
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+    pub fn is_online(&self) -> bool {
+        self.online.load(Ordering::Acquire)
+    }
+    pub(super) fn set_online(&self, online: bool) {
+        self.online.store(online, Ordering::Release);
+    }
     pub fn with_proxy(proxy: Option<String>) -> Result<Self> {
         Ok(if let Some(p) = proxy {
             Self::from_client(
@@ -64,31 +125,84 @@ impl Session {
             Self::new()
         })
     }
+    pub fn save(&self) -> SavedSession {
+        SavedSession {
+            handle: self.handle.clone(),
+            ftaa: self.ftaa.clone(),
+            cookies: self
+                .jar
+                .0
+                .read()
+                .unwrap()
+                .iter_unexpired()
+                .cloned()
+                .collect(),
+        }
+    }
+    pub fn restore(saved: SavedSession, proxy: Option<String>) -> Result<Self> {
+        let mut ret = Self::with_proxy(proxy)?;
+        // Cookies that expired since the session was saved are dropped.
+        *ret.jar.0.write().unwrap() = cookie_store::CookieStore::from_cookies(
+            saved.cookies.into_iter().map(Ok::<_, Infallible>),
+            false,
+        )
+        .unwrap();
+        ret.handle = saved.handle;
+        ret.ftaa = saved.ftaa;
+        Ok(ret)
+    }
+    pub async fn validate(&self) -> Result<bool> {
+        let body = async_retry(&self.retry, async || {
+            self.client
+                .get(HOME_URL)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        })
+        .await
+        .map_err(network_error)?;
+        let online = self.regex.session.login.is_match(body.as_str());
+        self.set_online(online);
+        Ok(online)
+    }
 
     #[allow(clippy::ptr_arg)]
     pub(super) fn find_csrf(&self, response: &String) -> Result<String> {
         search_text(response, &self.regex.session.csrf)
             .ok_or_else(|| Error::with_kind(Kind::Csrf(Box::new(regex_mismatch(None)))))
     }
+    async fn get_page(&self, url: &str) -> Result<String> {
+        self.client
+            .get(url)
+            .send()
+            .await
+            .map_err(csrf_network_error)?
+            .text()
+            .await
+            .map_err(csrf_network_error)
+    }
     pub(super) async fn get_csrf(&self, url: &str) -> Result<String> {
-        self.find_csrf(
-            &self
-                .client
-                .get(url)
-                .send()
-                .await
-                .map_err(csrf_network_error)?
-                .text()
-                .await
-                .map_err(csrf_network_error)?,
-        )
+        self.find_csrf(&self.get_page(url).await?)
+    }
+    pub(super) async fn get_online_csrf(&self, url: &str) -> Result<String> {
+        let body = self.get_page(url).await?;
+        if !self.regex.session.login.is_match(body.as_str()) {
+            self.set_online(false);
+            return Err(Error::with_kind(Kind::LoggedOut));
+        }
+        self.find_csrf(&body)
     }
 
     pub async fn login(&mut self, handle: String, password: &str) -> Result<()> {
-        const URL: &str = "https://codeforces.com/enter";
         self.handle = handle;
+        self.relogin(password).await
+    }
+    pub async fn relogin(&self, password: &str) -> Result<()> {
+        const URL: &str = "https://codeforces.com/enter";
         let csrf = self.get_csrf(URL).await?;
-        let body = async_retry(async || {
+        let body = async_retry(&self.retry, async || {
             self.client
                 .post(URL)
                 .form(&[
@@ -110,7 +224,7 @@ impl Session {
         .await
         .map_err(network_error)?;
         if self.regex.session.login.is_match(body.as_str()) {
-            self.online = true;
+            self.set_online(true);
             Ok(())
         } else {
             Err(Error::with_description(
@@ -119,18 +233,19 @@ impl Session {
             ))
         }
     }
-    pub async fn logout(&mut self) -> Result<()> {
-        if !self.online {
+    pub async fn logout(&self) -> Result<()> {
+        if !self.is_online() {
             return Ok(());
         }
         let url = search_response(
+            &self.retry,
             || self.client.get("https://codeforces.com"),
             &self.regex.session.logout,
         )
         .await
         .map_err(network_error)?
         .ok_or_else(|| Error::with_description(Kind::Regex, "Can't find logout url"))?;
-        async_retry(async || {
+        async_retry(&self.retry, async || {
             self.client
                 .get(format!("https://codeforces.com/{}/logout", url))
                 .send()
@@ -139,7 +254,7 @@ impl Session {
         })
         .await
         .map_err(network_error)?;
-        self.online = false;
+        self.set_online(false);
         Ok(())
     }
 }
@@ -1,22 +1,22 @@
 extern crate regex;
 extern crate reqwest;
+extern crate serde;
 extern crate tokio;
 
 use super::{
     error::{network_error, Error, Kind, Result},
     problem::Problem,
-    retry::async_retry,
+    retry::{async_retry, RetryPolicy},
     search::search_response,
     Session, Verdict,
 };
 use crate::config::judge::{session::BFAA, submit::CHECK_DELAY};
 use regex::Regex;
 use reqwest::Client;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
 use tokio::time::{sleep_until, Instant};
 
-const MAX_OUTPUT: usize = 500;
-
 pub(super) struct RegexSet {
     submit: Regex,
     last_submit: Regex,
@@ -30,22 +30,119 @@ impl RegexSet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    Duplicate,
+    TooLong,
+    Language,
+    ContestClosed,
+    Limit,
+}
+impl Rejection {
+    /// Checks the more specific phrases first, as a message about a source
+    /// length limit must not be taken for a submission limit.
+    fn parse(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        let has = |x: &[&str]| x.iter().any(|x| message.contains(x));
+        if has(&["exactly the same code"]) {
+            Some(Self::Duplicate)
+        } else if has(&["not longer than", "too long"]) {
+            Some(Self::TooLong)
+        } else if has(&[
+            "not running",
+            "not started",
+            "is over",
+            "has finished",
+            "has ended",
+            "closed",
+        ]) {
+            Some(Self::ContestClosed)
+        } else if has(&[
+            "too many submissions",
+            "submitted too many",
+            "too often",
+            "submission limit",
+            "try again later",
+        ]) {
+            Some(Self::Limit)
+        } else if has(&["language"]) {
+            Some(Self::Language)
+        } else {
+            None
+        }
+    }
+}
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Duplicate => "duplicate source",
+            Self::TooLong => "source too long",
+            Self::Language => "unknown language",
+            Self::ContestClosed => "contest not running",
+            Self::Limit => "submission limit reached",
+        })
+    }
+}
+pub(super) fn rejection_error(message: String) -> Error {
+    match Rejection::parse(&message) {
+        Some(r) => Error::with_description(Kind::Rejected(r), message),
+        None => Error::with_description(Kind::Api, message),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Submission {
-    client: Client,
+    problem: Problem,
+    handle: String,
     id: String,
+    #[serde(skip)]
+    client: Option<Client>,
+    #[serde(skip)]
     csrf_token: String,
+    #[serde(skip)]
+    retry: RetryPolicy,
 }
-fn full_data_or(data: String) -> Option<String> {
-    if data.len() > MAX_OUTPUT {
+/// Input and answer longer than the visible `length` are cut off by the judge.
+fn full_data_or(data: String, length: usize) -> Option<String> {
+    if data.len() > length {
         None
     } else {
         Some(data)
     }
 }
+fn strip_tags(html: &str) -> String {
+    let mut ret = String::new();
+    let mut tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => tag = true,
+            '>' => tag = false,
+            c if !tag => ret.push(c),
+            _ => (),
+        }
+    }
+    ret.trim().to_string()
+}
 impl Submission {
-    pub async fn poll(&self, id: usize) -> Result<Option<Verdict>> {
-        let mut data = async_retry(async || {
-            self.client
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+    pub fn handle(&self) -> &str {
+        self.handle.as_str()
+    }
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+    pub fn is_attached(&self) -> bool {
+        self.client.is_some()
+    }
+    pub async fn poll(&self, id: usize, length: usize) -> Result<Option<Verdict>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::with_kind(Kind::Detached))?;
+        let mut data = async_retry(&self.retry, async || {
+            client
                 .post("https://codeforces.com/data/submitSource")
                 .form(&[("submissionId", &self.id), ("csrf_token", &self.csrf_token)])
                 .send()
@@ -64,17 +161,18 @@ impl Submission {
                 return Err(Error::with_kind(Kind::TestCount(pos, id)));
             }
             return Ok(Some(Verdict {
-                input: full_data_or(data.remove(&format!("input#{}", pos)).unwrap()),
+                verdict: strip_tags(&data["verdict"]),
+                input: full_data_or(data.remove(&format!("input#{}", pos)).unwrap(), length),
                 output: data.remove(&format!("output#{}", pos)).unwrap(),
-                answer: full_data_or(data.remove(&format!("answer#{}", pos)).unwrap()),
+                answer: full_data_or(data.remove(&format!("answer#{}", pos)).unwrap(), length),
             }));
         }
     }
-    pub async fn wait(&self, id: usize) -> Result<Verdict> {
+    pub async fn wait(&self, id: usize, length: usize) -> Result<Verdict> {
         let mut next = Instant::now();
         loop {
             sleep_until(next).await;
-            if let Some(v) = self.poll(id).await? {
+            if let Some(v) = self.poll(id, length).await? {
                 return Ok(v);
             }
             next += CHECK_DELAY;
@@ -84,9 +182,10 @@ impl Submission {
 
 impl Session {
     pub async fn get_last_submission(&self, problem: &Problem) -> Result<Submission> {
-        let csrf = self.get_csrf(problem.status_url.as_str()).await?;
+        let csrf = self.get_online_csrf(problem.status_url.as_str()).await?;
         Ok(Submission {
             id: search_response(
+                &self.retry,
                 || {
                     self.client
                         .post(&problem.status_url)
@@ -108,13 +207,25 @@ impl Session {
             .await
             .map_err(network_error)?
             .ok_or_else(|| Error::with_kind(Kind::Regex))?,
-            client: self.client.clone(),
+            problem: problem.clone(),
+            client: Some(self.client.clone()),
+            handle: self.handle.clone(),
             csrf_token: csrf,
+            retry: self.retry,
         })
     }
+    pub async fn attach(&self, submission: &mut Submission) -> Result<()> {
+        submission.csrf_token = self
+            .get_online_csrf(submission.problem.status_url.as_str())
+            .await?;
+        submission.client = Some(self.client.clone());
+        submission.retry = self.retry;
+        Ok(())
+    }
     pub async fn submit(&self, problem: &Problem, language: &str, code: &str) -> Result<()> {
-        let csrf = self.get_csrf(problem.submit_url.as_str()).await?;
+        let csrf = self.get_online_csrf(problem.submit_url.as_str()).await?;
         search_response(
+            &self.retry,
             || {
                 self.client
                     .post(&problem.submit_url)
@@ -137,6 +248,6 @@ impl Session {
         )
         .await
         .map_err(network_error)?
-        .map_or(Ok(()), |x| Err(Error::with_description(Kind::Api, x)))
+        .map_or(Ok(()), |x| Err(rejection_error(x)))
     }
 }
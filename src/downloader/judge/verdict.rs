@@ -1,5 +1,6 @@
 extern crate serde;
 
+use crate::config::chunk::TRUNCATION_MARKER;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -7,4 +8,21 @@ pub struct Verdict {
     pub(crate) input: Option<String>,
     pub(crate) output: String,
     pub(crate) answer: Option<String>,
+    #[serde(default)]
+    pub(crate) verdict: String,
+}
+impl Verdict {
+    pub fn verdict(&self) -> &str {
+        self.verdict.as_str()
+    }
+    /// Length of the visible output if the judge cut it short. The marker
+    /// may also be printed by the program itself, so an output of the
+    /// `expected` length is never taken as truncated.
+    pub fn truncated(&self, expected: usize) -> Option<usize> {
+        let output = self.output.trim_end_matches(&['\r', '\n'][..]);
+        if output.len() == expected {
+            return None;
+        }
+        output.strip_suffix(TRUNCATION_MARKER).map(str::len)
+    }
 }
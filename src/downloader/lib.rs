@@ -8,6 +8,7 @@ pub mod downloader;
 mod email;
 pub mod encoding;
 pub mod error;
+pub mod job;
 pub mod judge;
 mod random;
 pub mod submitter;
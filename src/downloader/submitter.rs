@@ -1,23 +1,107 @@
+extern crate tokio;
+
+pub mod audit;
+pub mod budget;
 pub mod error;
+pub mod guard;
 mod list;
 pub mod session;
+pub mod status;
 pub mod submit;
 
 pub use error::{Error, Result};
 
+use crate::{
+    account::Account,
+    judge::{retry::RetryPolicy, Session},
+};
+use budget::Exceeded;
+use error::{Kind, Operate};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
+use tokio::time::sleep_until;
+
 pub struct Submitter {
-    session: std::vec::Vec<crate::judge::Session>,
-    list: list::AccountList,
+    session: RwLock<Vec<Option<Arc<Session>>>>,
+    account: Mutex<HashMap<String, Account>>,
+    list: Mutex<list::AccountList>,
+    event: Mutex<Vec<status::Event>>,
+    budget: Mutex<budget::BudgetState>,
+    audit: Mutex<Option<PathBuf>>,
+    guard: RwLock<Arc<guard::ContestGuard>>,
+    retry: Mutex<RetryPolicy>,
 }
 impl Submitter {
     pub fn new() -> Self {
         Submitter {
-            session: Vec::new(),
-            list: list::AccountList::new(),
+            session: RwLock::new(Vec::new()),
+            account: Mutex::new(HashMap::new()),
+            list: Mutex::new(list::AccountList::new()),
+            event: Mutex::new(Vec::new()),
+            budget: Mutex::new(budget::BudgetState::default()),
+            audit: Mutex::new(None),
+            guard: RwLock::new(Arc::new(guard::ContestGuard::default())),
+            retry: Mutex::new(RetryPolicy::default()),
         }
     }
     pub fn is_empty(&self) -> bool {
-        self.session.is_empty()
+        self.session.read().unwrap().iter().all(Option::is_none)
+    }
+    pub fn find_session(&self, handle: &str) -> Option<Arc<Session>> {
+        self.session
+            .read()
+            .unwrap()
+            .iter()
+            .flatten()
+            .find(|x| x.handle == handle)
+            .cloned()
+    }
+    fn session_count(&self) -> usize {
+        self.session.read().unwrap().iter().flatten().count()
+    }
+    fn find_id(&self, handle: &str) -> Option<usize> {
+        self.session
+            .read()
+            .unwrap()
+            .iter()
+            .position(|x| matches!(x, Some(s) if s.handle == handle))
+    }
+    fn get_session(&self, id: usize) -> Option<Arc<Session>> {
+        self.session.read().unwrap()[id].clone()
+    }
+    async fn next_session(&self) -> Result<(usize, Arc<Session>)> {
+        loop {
+            // Accounts over their daily budget would only waste their slot.
+            let exhausted = self.exhausted_accounts();
+            let skip: Vec<usize> = exhausted
+                .iter()
+                .flat_map(|(_, v)| v.iter().filter_map(|h| self.find_id(h)))
+                .collect();
+            let reserved = self.list.lock().unwrap().reserve(|id| !skip.contains(&id));
+            let (id, time, restored) = match (reserved, exhausted) {
+                (Some(v), _) => v,
+                (None, Some((v, _))) if !skip.is_empty() => {
+                    return Err(Error::budget_exceeded(Exceeded::Account(v)))
+                }
+                (None, _) => {
+                    return Err(Error {
+                        operate: Operate::Submit,
+                        kind: Kind::NoAccount,
+                        handle: String::new(),
+                    })
+                }
+            };
+            sleep_until(time).await;
+            if let Some(s) = self.get_session(id) {
+                if restored {
+                    self.push_event(status::Event::Restored(s.handle.clone()));
+                }
+                return Ok((id, s));
+            }
+        }
     }
 }
 impl Default for Submitter {
@@ -0,0 +1,249 @@
+extern crate openssl;
+extern crate serde;
+extern crate serde_json;
+
+use super::Submitter;
+use crate::{
+    cache::SubmitKey,
+    judge::{problem::Problem, submit::Submission, Session},
+    random::random_hex,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug)]
+pub enum AuditError {
+    Io(PathBuf, io::Error),
+    Json(usize, serde_json::Error),
+}
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "Error accessing {}: {}", path.display(), e),
+            Self::Json(line, e) => write!(f, "Error parse audit log line {}: {}", line, e),
+        }
+    }
+}
+impl StdError for AuditError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            Self::Json(_, e) => Some(e),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Purpose {
+    Meta,
+    Data,
+    Calibrate,
+}
+impl fmt::Display for Purpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Meta => "meta",
+            Self::Data => "data",
+            Self::Calibrate => "calibrate",
+        })
+    }
+}
+
+const ENTRY_LEN: usize = 16;
+
+/// Written before the code is posted, the submission id or error follows in
+/// a separate line once known.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Record {
+    #[serde(default)]
+    pub entry: String,
+    pub time: u64,
+    pub handle: String,
+    pub problem: String,
+    pub language: String,
+    pub purpose: Purpose,
+    pub key: SubmitKey,
+    pub hash: String,
+    pub submission: Option<String>,
+    pub error: Option<String>,
+    pub verdict: Option<String>,
+}
+#[derive(Serialize, Deserialize)]
+struct Judged {
+    time: u64,
+    submission: String,
+    verdict: String,
+}
+#[derive(Serialize, Deserialize)]
+struct Resolved {
+    time: u64,
+    entry: String,
+    submission: Option<String>,
+    error: Option<String>,
+}
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Line {
+    Submit(Record),
+    Judged(Judged),
+    Resolved(Resolved),
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs())
+}
+fn hash(code: &str) -> String {
+    openssl::sha::sha256(code.as_bytes())
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect()
+}
+
+fn append(path: &Path, line: &Line) -> Result<(), AuditError> {
+    let mut data = serde_json::to_vec(line).map_err(|e| AuditError::Json(0, e))?;
+    data.push(b'\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(&data))
+        .map_err(|e| AuditError::Io(path.to_path_buf(), e))
+}
+
+pub struct Filter<'a> {
+    pub problem: Option<&'a str>,
+    pub handle: Option<&'a str>,
+}
+impl Filter<'_> {
+    fn matches(&self, record: &Record) -> bool {
+        self.handle.iter().all(|&h| record.handle == h)
+            && self
+                .problem
+                .iter()
+                .all(|&p| record.problem == p || record.problem.ends_with(&format!("-{}", p)))
+    }
+}
+
+pub fn history(path: &Path, filter: &Filter) -> Result<Vec<Record>, AuditError> {
+    let file = File::open(path).map_err(|e| AuditError::Io(path.to_path_buf(), e))?;
+    let mut ret = Vec::new();
+    let mut entry = HashMap::new();
+    let mut index = HashMap::new();
+    for (num, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| AuditError::Io(path.to_path_buf(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line).map_err(|e| AuditError::Json(num + 1, e))? {
+            Line::Submit(r) => {
+                if !r.entry.is_empty() {
+                    entry.insert(r.entry.clone(), ret.len());
+                }
+                if let Some(id) = &r.submission {
+                    index.insert(id.clone(), ret.len());
+                }
+                ret.push(r);
+            }
+            Line::Resolved(v) => {
+                if let Some(&i) = entry.get(&v.entry) {
+                    if let Some(id) = &v.submission {
+                        index.insert(id.clone(), i);
+                    }
+                    let r: &mut Record = &mut ret[i];
+                    r.submission = v.submission;
+                    r.error = v.error;
+                }
+            }
+            Line::Judged(j) => {
+                if let Some(&i) = index.get(&j.submission) {
+                    let r: &mut Record = &mut ret[i];
+                    r.verdict = Some(j.verdict);
+                }
+            }
+        }
+    }
+    ret.retain(|r| filter.matches(r));
+    Ok(ret)
+}
+
+impl Submitter {
+    pub fn set_audit_log(&self, path: Option<PathBuf>) {
+        *self.audit.lock().unwrap() = path;
+    }
+    pub fn audit_log(&self) -> Option<PathBuf> {
+        self.audit.lock().unwrap().clone()
+    }
+    fn write_audit(&self, line: &Line) {
+        let audit = self.audit.lock().unwrap();
+        if let Some(path) = audit.as_ref() {
+            if let Err(e) = append(path, line) {
+                drop(audit);
+                self.push_event(super::status::Event::Audit(e));
+            }
+        }
+    }
+    /// Log a submission about to be posted, returns its entry for
+    /// `audit_submit`.
+    pub(super) fn draft(
+        &self,
+        session: &Session,
+        problem: &Problem,
+        language: &str,
+        code: &str,
+        key: SubmitKey,
+        purpose: Purpose,
+    ) -> Option<String> {
+        self.audit.lock().unwrap().as_ref()?;
+        let entry = random_hex(ENTRY_LEN);
+        self.write_audit(&Line::Submit(Record {
+            entry: entry.clone(),
+            time: now(),
+            handle: session.handle.clone(),
+            problem: problem.to_string(),
+            language: language.to_string(),
+            purpose,
+            key,
+            hash: hash(code),
+            submission: None,
+            error: None,
+            verdict: None,
+        }));
+        Some(entry)
+    }
+    pub(super) fn audit_submit<E: fmt::Display>(
+        &self,
+        entry: Option<String>,
+        result: Result<&Submission, &E>,
+    ) {
+        if let Some(entry) = entry {
+            let (submission, error) = match result {
+                Ok(s) => (Some(s.id().to_string()), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            self.write_audit(&Line::Resolved(Resolved {
+                time: now(),
+                entry,
+                submission,
+                error,
+            }));
+        }
+    }
+    pub fn audit_verdict(&self, submission: &Submission, verdict: &str) {
+        self.write_audit(&Line::Judged(Judged {
+            time: now(),
+            submission: submission.id().to_string(),
+            verdict: verdict.to_string(),
+        }));
+    }
+}
@@ -0,0 +1,210 @@
+extern crate serde;
+extern crate serde_yaml;
+
+use super::Submitter;
+use crate::judge::problem::Problem;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    fmt,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug)]
+pub enum UsageError {
+    Io(PathBuf, io::Error),
+    Yaml(serde_yaml::Error),
+}
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "Error accessing {}: {}", path.display(), e),
+            Self::Yaml(e) => write!(f, "Error process usage: {}", e),
+        }
+    }
+}
+impl StdError for UsageError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            Self::Yaml(e) => Some(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exceeded {
+    Run(usize),
+    Account(usize),
+    Problem(usize),
+}
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Run(v) => write!(f, "run budget of {} submissions used up", v),
+            Self::Account(v) => write!(f, "daily budget of {} submissions per account used up", v),
+            Self::Problem(v) => write!(f, "problem budget of {} submissions used up", v),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Budget {
+    pub per_run: Option<usize>,
+    pub per_account_daily: Option<usize>,
+    pub per_problem: Option<usize>,
+    pub usage: Option<PathBuf>,
+}
+impl Budget {
+    pub fn from_reader<R: Read>(rdr: R) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_reader(rdr)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Usage {
+    day: u64,
+    account: HashMap<String, usize>,
+    problem: HashMap<String, usize>,
+}
+#[derive(Default)]
+pub(super) struct BudgetState {
+    budget: Budget,
+    usage: Usage,
+    run: usize,
+}
+
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs() / DAY)
+}
+
+impl BudgetState {
+    fn roll(&mut self) {
+        let day = today();
+        if self.usage.day != day {
+            self.usage.day = day;
+            self.usage.account.clear();
+        }
+    }
+    fn check(&mut self, handle: &str, problem: &str) -> Result<(), Exceeded> {
+        self.roll();
+        if let Some(v) = self.budget.per_run {
+            if self.run >= v {
+                return Err(Exceeded::Run(v));
+            }
+        }
+        if let Some(v) = self.budget.per_problem {
+            if self.usage.problem.get(problem).copied().unwrap_or(0) >= v {
+                return Err(Exceeded::Problem(v));
+            }
+        }
+        if let Some(v) = self.budget.per_account_daily {
+            if self.usage.account.get(handle).copied().unwrap_or(0) >= v {
+                return Err(Exceeded::Account(v));
+            }
+        }
+        Ok(())
+    }
+    /// Count a submission before it is made, so that concurrent submits
+    /// can't overrun the budget.
+    fn reserve(&mut self, handle: &str, problem: &str) -> Result<(), Exceeded> {
+        self.check(handle, problem)?;
+        self.run += 1;
+        *self.usage.account.entry(handle.to_string()).or_insert(0) += 1;
+        *self.usage.problem.entry(problem.to_string()).or_insert(0) += 1;
+        Ok(())
+    }
+    fn release(&mut self, handle: &str, problem: &str) {
+        self.run = self.run.saturating_sub(1);
+        if let Some(v) = self.usage.account.get_mut(handle) {
+            *v = v.saturating_sub(1);
+        }
+        if let Some(v) = self.usage.problem.get_mut(problem) {
+            *v = v.saturating_sub(1);
+        }
+    }
+    fn save(&self) -> Result<(), UsageError> {
+        match &self.budget.usage {
+            Some(path) => save(path, &self.usage),
+            None => Ok(()),
+        }
+    }
+    /// Accounts that used up their daily budget, with the budget.
+    fn exhausted(&mut self) -> Option<(usize, Vec<String>)> {
+        self.roll();
+        let v = self.budget.per_account_daily?;
+        Some((
+            v,
+            self.usage
+                .account
+                .iter()
+                .filter(|(_, &used)| used >= v)
+                .map(|(handle, _)| handle.clone())
+                .collect(),
+        ))
+    }
+}
+
+fn save(path: &Path, usage: &Usage) -> Result<(), UsageError> {
+    let tmp = path.with_extension("tmp");
+    serde_yaml::to_writer(
+        File::create(&tmp).map_err(|e| UsageError::Io(tmp.clone(), e))?,
+        usage,
+    )
+    .map_err(UsageError::Yaml)?;
+    fs::rename(&tmp, path).map_err(|e| UsageError::Io(path.to_path_buf(), e))
+}
+
+impl Submitter {
+    pub fn set_budget(&self, budget: Budget) -> Result<(), UsageError> {
+        let usage = match &budget.usage {
+            Some(path) if path.exists() => serde_yaml::from_reader(
+                File::open(path).map_err(|e| UsageError::Io(path.clone(), e))?,
+            )
+            .map_err(UsageError::Yaml)?,
+            _ => Usage::default(),
+        };
+        let mut state = self.budget.lock().unwrap();
+        state.budget = budget;
+        state.usage = usage;
+        Ok(())
+    }
+    pub fn begin_run(&self) {
+        self.budget.lock().unwrap().run = 0;
+    }
+    pub fn used(&self, problem: &Problem) -> usize {
+        let state = self.budget.lock().unwrap();
+        state
+            .usage
+            .problem
+            .get(&problem.to_string())
+            .copied()
+            .unwrap_or(0)
+    }
+    pub(super) fn reserve_budget(&self, handle: &str, problem: &Problem) -> Result<(), Exceeded> {
+        self.budget
+            .lock()
+            .unwrap()
+            .reserve(handle, &problem.to_string())
+    }
+    pub(super) fn release_budget(&self, handle: &str, problem: &Problem) {
+        self.budget
+            .lock()
+            .unwrap()
+            .release(handle, &problem.to_string())
+    }
+    pub(super) fn save_budget(&self) -> Result<(), UsageError> {
+        self.budget.lock().unwrap().save()
+    }
+    pub(super) fn exhausted_accounts(&self) -> Option<(usize, Vec<String>)> {
+        self.budget.lock().unwrap().exhausted()
+    }
+}
@@ -1,7 +1,8 @@
 extern crate tokio;
 
-use crate::judge;
-use std::{error::Error as StdError, fmt, result::Result as StdResult};
+use super::{budget::Exceeded, guard::Refused};
+use crate::judge::{self, retry::ErrorClass, submit::Rejection};
+use std::{error::Error as StdError, fmt, result::Result as StdResult, sync::Arc};
 use tokio::task::JoinError;
 
 #[derive(Debug, Clone, Copy)]
@@ -10,12 +11,20 @@ pub(super) enum Operate {
     Login,
     Submit,
     GetSubmission,
+    Attach,
     Logout,
+    Remove,
 }
 #[derive(Debug)]
 pub(super) enum Kind {
     Join(JoinError),
     Judge(judge::Error),
+    Offline,
+    Unknown,
+    NoAccount,
+    Aborted(Rejection),
+    Budget(Exceeded),
+    Refused(Arc<Refused>),
 }
 #[derive(Debug)]
 pub struct Error {
@@ -36,6 +45,24 @@ impl fmt::Display for Error {
                     self.operate, self.handle, x
                 )
             }
+            Kind::Offline => write!(
+                f,
+                "Error while {}: {} isn't logged in",
+                self.operate, self.handle
+            ),
+            Kind::Unknown => write!(
+                f,
+                "Error while {}: no account {}",
+                self.operate, self.handle
+            ),
+            Kind::NoAccount => write!(f, "Error while {}: no account available", self.operate),
+            Kind::Aborted(r) => write!(
+                f,
+                "Skipped {}: earlier submission rejected ({})",
+                self.operate, r
+            ),
+            Kind::Budget(e) => write!(f, "Stopped {}: {}", self.operate, e),
+            Kind::Refused(e) => write!(f, "Refused {}: {}", self.operate, e),
         }
     }
 }
@@ -44,6 +71,60 @@ impl StdError for Error {
         match &self.kind {
             Kind::Join(x) => Some(x),
             Kind::Judge(x) => Some(x),
+            Kind::Refused(x) => Some(x.as_ref()),
+            Kind::Offline
+            | Kind::Unknown
+            | Kind::NoAccount
+            | Kind::Aborted(_)
+            | Kind::Budget(_) => None,
+        }
+    }
+}
+impl Error {
+    pub(crate) fn aborted(rejection: Rejection) -> Self {
+        Self {
+            operate: Operate::Submit,
+            kind: Kind::Aborted(rejection),
+            handle: String::new(),
+        }
+    }
+    pub(crate) fn budget_exceeded(exceeded: Exceeded) -> Self {
+        Self {
+            operate: Operate::Submit,
+            kind: Kind::Budget(exceeded),
+            handle: String::new(),
+        }
+    }
+    pub(crate) fn refused(refused: Arc<Refused>) -> Self {
+        Self {
+            operate: Operate::Submit,
+            kind: Kind::Refused(refused),
+            handle: String::new(),
+        }
+    }
+    pub(super) fn is_logged_out(&self) -> bool {
+        matches!(&self.kind, Kind::Judge(e) if e.is_logged_out())
+    }
+    pub fn is_refused(&self) -> bool {
+        matches!(self.kind, Kind::Refused(_))
+    }
+    pub fn budget(&self) -> Option<Exceeded> {
+        match &self.kind {
+            Kind::Budget(e) => Some(*e),
+            _ => None,
+        }
+    }
+    pub fn rejection(&self) -> Option<Rejection> {
+        match &self.kind {
+            Kind::Judge(e) => e.rejection(),
+            Kind::Aborted(r) => Some(*r),
+            _ => None,
+        }
+    }
+    pub(super) fn class(&self) -> ErrorClass {
+        match &self.kind {
+            Kind::Judge(e) => e.class(),
+            _ => ErrorClass::Permanent,
         }
     }
 }
@@ -54,7 +135,9 @@ impl fmt::Display for Operate {
             Self::Login => f.write_str("login"),
             Self::Submit => f.write_str("submitting code"),
             Self::GetSubmission => f.write_str("getting submission"),
+            Self::Attach => f.write_str("attaching submission"),
             Self::Logout => f.write_str("logout"),
+            Self::Remove => f.write_str("removing account"),
         }
     }
 }
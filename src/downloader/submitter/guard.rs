@@ -0,0 +1,94 @@
+use super::Submitter;
+use crate::judge::{
+    self,
+    contest::{ContestApi, Phase},
+    problem::Problem,
+};
+use std::{
+    collections::HashSet,
+    error::Error as StdError,
+    fmt,
+    result::Result as StdResult,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug)]
+pub enum Refused {
+    Live(String, Phase),
+    Check(String, judge::Error),
+}
+impl fmt::Display for Refused {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Live(contest, phase) => write!(
+                f,
+                "contest {} is {}, refusing to submit without override",
+                contest, phase
+            ),
+            Self::Check(contest, e) => write!(
+                f,
+                "can't check phase of contest {}, refusing to submit without override: {}",
+                contest, e
+            ),
+        }
+    }
+}
+impl StdError for Refused {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Live(_, _) => None,
+            Self::Check(_, e) => Some(e),
+        }
+    }
+}
+
+pub struct ContestGuard {
+    api: ContestApi,
+    allow_live: bool,
+    finished: Mutex<HashSet<String>>,
+}
+impl Default for ContestGuard {
+    fn default() -> Self {
+        Self::new(ContestApi::default(), false)
+    }
+}
+impl ContestGuard {
+    pub fn new(api: ContestApi, allow_live: bool) -> Self {
+        Self {
+            api,
+            allow_live,
+            finished: Mutex::new(HashSet::new()),
+        }
+    }
+    /// Gym contests in practice mode report `FINISHED` as well.
+    pub async fn check(&self, problem: &Problem) -> StdResult<(), Refused> {
+        if self.allow_live || self.finished.lock().unwrap().contains(&problem.contest) {
+            return Ok(());
+        }
+        let contest = self
+            .api
+            .contest(&problem.contest)
+            .await
+            .map_err(|e| Refused::Check(problem.contest.clone(), e))?;
+        match contest.phase {
+            Phase::Finished => {
+                self.finished
+                    .lock()
+                    .unwrap()
+                    .insert(problem.contest.clone());
+                Ok(())
+            }
+            p => Err(Refused::Live(problem.contest.clone(), p)),
+        }
+    }
+}
+
+impl Submitter {
+    pub fn set_contest_guard(&self, guard: ContestGuard) {
+        *self.guard.write().unwrap() = Arc::new(guard);
+    }
+    pub(super) async fn check_contest(&self, problem: &Problem) -> StdResult<(), Arc<Refused>> {
+        let guard = self.guard.read().unwrap().clone();
+        guard.check(problem).await.map_err(Arc::new)
+    }
+}
@@ -1,52 +1,143 @@
 extern crate tokio;
 
-use crate::config::submitter::{DELAY_PER_ACCOUNT, SUBMIT_DELAY};
+use crate::config::submitter::{
+    DELAY_PER_ACCOUNT, MAX_SUBMIT_DELAY, QUARANTINE_COOLDOWN, QUARANTINE_THRESHOLD, SUBMIT_DELAY,
+    SUBMIT_DELAY_RECOVER,
+};
 use std::{
-    cmp::{max, Reverse},
+    cmp::{max, min, Reverse},
     collections::BinaryHeap,
+    time::Duration,
 };
-use tokio::time::{sleep_until, Instant};
+use tokio::time::Instant;
 
 #[derive(Eq, PartialEq, PartialOrd, Ord)]
 struct AccountNode {
     next_submit: Instant,
     id: usize,
 }
+pub(super) struct AccountState {
+    pub(super) submitted: usize,
+    pub(super) success: usize,
+    pub(super) failure: usize,
+    pub(super) next_submit: Instant,
+    pub(super) quarantined: Option<Instant>,
+    failure_streak: usize,
+    active: bool,
+}
 pub(super) struct AccountList {
     heap: BinaryHeap<Reverse<AccountNode>>,
+    account: Vec<AccountState>,
     next_submit: Instant,
+    pub(super) submit_delay: Duration,
 }
 
 impl AccountList {
     pub(super) fn new() -> Self {
         Self {
             heap: BinaryHeap::new(),
+            account: Vec::new(),
             next_submit: Instant::now(),
+            submit_delay: SUBMIT_DELAY,
         }
     }
     pub(super) fn expand(&mut self, count: usize) {
-        let base = self.heap.len();
+        let base = self.account.len();
         let now = Instant::now();
         self.heap.reserve(count);
         for id in base..base + count {
+            self.account.push(AccountState {
+                submitted: 0,
+                success: 0,
+                failure: 0,
+                next_submit: now,
+                quarantined: None,
+                failure_streak: 0,
+                active: true,
+            });
             self.heap.push(Reverse(AccountNode {
                 next_submit: now,
                 id,
             }));
         }
     }
-    pub(super) async fn get(&mut self) -> usize {
-        let account = self.heap.pop().unwrap().0;
-        sleep_until(max(account.next_submit, self.next_submit)).await;
-        let now = Instant::now();
-        self.next_submit = now + SUBMIT_DELAY;
+    /// Take the account that can submit first among those `usable` accepts.
+    pub(super) fn reserve<F: Fn(usize) -> bool>(
+        &mut self,
+        usable: F,
+    ) -> Option<(usize, Instant, bool)> {
+        let mut skipped = Vec::new();
+        let account = loop {
+            match self.heap.pop() {
+                Some(v) if usable(v.0.id) => break Some(v.0),
+                Some(v) => skipped.push(v),
+                None => break None,
+            }
+        };
+        self.heap.extend(skipped);
+        let account = account?;
+        let time = max(max(account.next_submit, self.next_submit), Instant::now());
+        self.next_submit = time + self.submit_delay;
+        let state = &mut self.account[account.id];
+        let restored = state.quarantined.take().is_some();
+        state.submitted += 1;
+        state.next_submit = time + DELAY_PER_ACCOUNT;
         self.heap.push(Reverse(AccountNode {
-            next_submit: now + DELAY_PER_ACCOUNT,
+            next_submit: state.next_submit,
             id: account.id,
         }));
-        account.id
+        Some((account.id, time, restored))
+    }
+    pub(super) fn report(&mut self, id: usize, success: bool) -> Option<Instant> {
+        let state = &mut self.account[id];
+        if success {
+            state.success += 1;
+            state.failure_streak = 0;
+            return None;
+        }
+        state.failure += 1;
+        state.failure_streak += 1;
+        if state.failure_streak < QUARANTINE_THRESHOLD || !state.active {
+            return None;
+        }
+        let until = Instant::now() + QUARANTINE_COOLDOWN;
+        state.failure_streak = 0;
+        state.quarantined = Some(until);
+        state.next_submit = max(state.next_submit, until);
+        let next_submit = state.next_submit;
+        self.heap.retain(|x| x.0.id != id);
+        self.heap.push(Reverse(AccountNode { next_submit, id }));
+        Some(until)
+    }
+    pub(super) fn slow_down(&mut self) {
+        self.submit_delay = min(self.submit_delay * 2, MAX_SUBMIT_DELAY);
+        self.next_submit = max(self.next_submit, Instant::now() + self.submit_delay);
+    }
+    pub(super) fn speed_up(&mut self) {
+        self.submit_delay = max(
+            self.submit_delay.saturating_sub(SUBMIT_DELAY_RECOVER),
+            SUBMIT_DELAY,
+        );
+    }
+    pub(super) fn remove(&mut self, id: usize) {
+        self.heap.retain(|x| x.0.id != id);
+        self.account[id].active = false;
+    }
+    pub(super) fn activate(&mut self, id: usize) {
+        let state = &mut self.account[id];
+        if !state.active {
+            state.active = true;
+            self.heap.push(Reverse(AccountNode {
+                next_submit: state.next_submit,
+                id,
+            }));
+        }
+    }
+    pub(super) fn state(&self, id: usize) -> &AccountState {
+        &self.account[id]
     }
     pub(super) fn clear(&mut self) {
         self.heap.clear();
+        self.account.clear();
     }
 }
@@ -2,76 +2,205 @@ extern crate futures;
 extern crate tokio;
 
 use super::{
-    error::{Error, Kind, Operate},
+    error::{Error, Kind, Operate, Result},
     Submitter,
 };
-use crate::{account::Account, judge::Session};
+use crate::{
+    account::Account,
+    judge::{retry::RetryPolicy, session::SavedSession, Session},
+};
 use futures::future::join_all;
-use std::vec::Vec;
+use std::{collections::HashMap, sync::Arc, vec::Vec};
+
+async fn login_account(
+    account: Account,
+    saved: Option<SavedSession>,
+    retry: RetryPolicy,
+) -> Result<Session> {
+    let Account {
+        handle,
+        password,
+        proxy,
+    } = account;
+    if let Some(saved) = saved {
+        if let Ok(mut s) = Session::restore(saved, proxy.clone()) {
+            s.set_retry_policy(retry);
+            if let Ok(true) = s.validate().await {
+                return Ok(s);
+            }
+        }
+    }
+    let mut p = Session::with_proxy(proxy).map_err(|e| Error {
+        operate: Operate::BuildClient,
+        kind: Kind::Judge(e),
+        handle: handle.clone(),
+    })?;
+    p.set_retry_policy(retry);
+    match p.login(handle, password.as_str()).await {
+        Ok(_) => Ok(p),
+        Err(e) => Err(Error {
+            operate: Operate::Login,
+            kind: Kind::Judge(e),
+            handle: p.handle,
+        }),
+    }
+}
 
 impl Submitter {
-    pub async fn login<It: IntoIterator<Item = Account>>(&mut self, accounts: It) -> Vec<Error> {
-        let old_size = self.session.len();
+    /// Used by every session logged in or added afterwards.
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        *self.retry.lock().unwrap() = policy;
+    }
+    pub fn retry_policy(&self) -> RetryPolicy {
+        *self.retry.lock().unwrap()
+    }
+    pub async fn login<It: IntoIterator<Item = Account>>(&self, accounts: It) -> Vec<Error> {
+        self.restore(accounts, Vec::new()).await
+    }
+    pub async fn restore<It: IntoIterator<Item = Account>>(
+        &self,
+        accounts: It,
+        saved: Vec<SavedSession>,
+    ) -> Vec<Error> {
+        let mut saved: HashMap<String, SavedSession> =
+            saved.into_iter().map(|x| (x.handle.clone(), x)).collect();
         let mut err = Vec::new();
-        join_all(accounts.into_iter().map(
-            async move |Account {
-                            handle,
-                            password,
-                            proxy,
-                        }| {
-                let mut p = Session::with_proxy(proxy).map_err(|e| Error {
-                    operate: Operate::BuildClient,
-                    kind: Kind::Judge(e),
-                    handle: handle.clone(),
-                })?;
-                match p.login(handle, password.as_str()).await {
-                    Ok(_) => Ok(p),
-                    Err(e) => Err(Error {
-                        operate: Operate::Login,
-                        kind: Kind::Judge(e),
-                        handle: p.handle,
-                    }),
-                }
-            },
-        ))
+        let mut logged = Vec::new();
+        let retry = self.retry_policy();
+        join_all(accounts.into_iter().map(|x| {
+            self.account
+                .lock()
+                .unwrap()
+                .insert(x.handle.clone(), x.clone());
+            let s = saved.remove(&x.handle);
+            login_account(x, s, retry)
+        }))
         .await
         .into_iter()
         .for_each(|x| match x {
-            Ok(v) => self.session.push(v),
+            Ok(v) => logged.push(v),
             Err(e) => err.push(e),
         });
-        self.list.expand(self.session.len() - old_size);
-        return err;
+        self.add_session(logged).await;
+        err
+    }
+    pub fn save(&self) -> Vec<SavedSession> {
+        self.session
+            .read()
+            .unwrap()
+            .iter()
+            .flatten()
+            .filter(|x| x.is_online())
+            .map(|x| x.save())
+            .collect()
+    }
+    pub async fn relogin(&self, handle: &str) -> Result<()> {
+        let account = self
+            .account
+            .lock()
+            .unwrap()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| Error {
+                operate: Operate::Login,
+                kind: Kind::Unknown,
+                handle: handle.to_string(),
+            })?;
+        let session = Arc::new(login_account(account, None, self.retry_policy()).await?);
+        match self.find_id(handle) {
+            Some(id) => {
+                self.session.write().unwrap()[id] = Some(session);
+                self.list.lock().unwrap().activate(id);
+            }
+            None => {
+                self.session.write().unwrap().push(Some(session));
+                self.list.lock().unwrap().expand(1);
+            }
+        }
+        Ok(())
+    }
+    pub(super) async fn recover(&self, session: &Session) -> bool {
+        let password = self
+            .account
+            .lock()
+            .unwrap()
+            .get(&session.handle)
+            .map(|x| x.password.clone());
+        if let Some(p) = password {
+            if session.relogin(&p).await.is_ok() {
+                return true;
+            }
+        }
+        if let Some(id) = self.find_id(&session.handle) {
+            self.list.lock().unwrap().remove(id);
+        }
+        false
+    }
+    pub async fn remove(&self, handle: &str) -> Result<()> {
+        let known = self.account.lock().unwrap().remove(handle).is_some();
+        let id = match self.find_id(handle) {
+            Some(id) => id,
+            None if known => return Ok(()),
+            None => {
+                return Err(Error {
+                    operate: Operate::Remove,
+                    kind: Kind::Unknown,
+                    handle: handle.to_string(),
+                })
+            }
+        };
+        self.list.lock().unwrap().remove(id);
+        let session = self.session.write().unwrap()[id].take();
+        match session {
+            Some(s) => s.logout().await.map_err(|e| Error {
+                operate: Operate::Logout,
+                kind: Kind::Judge(e),
+                handle: handle.to_string(),
+            }),
+            None => Ok(()),
+        }
     }
 
-    pub async fn add_session<It: IntoIterator<Item = Session>>(&mut self, sessions: It) {
-        let p = sessions.into_iter();
-        let old = self.session.len();
-        self.session.extend(p);
-        self.list.expand(self.session.len() - old);
+    pub async fn add_session<It: IntoIterator<Item = Session>>(&self, sessions: It) {
+        let retry = self.retry_policy();
+        let count = {
+            let mut lock = self.session.write().unwrap();
+            let old = lock.len();
+            lock.extend(sessions.into_iter().map(|mut x| {
+                x.set_retry_policy(retry);
+                Some(Arc::new(x))
+            }));
+            lock.len() - old
+        };
+        self.list.lock().unwrap().expand(count);
     }
 
-    pub async fn logout(&mut self) -> Vec<Error> {
-        let ret: Vec<Error> =
-            join_all(
-                self.session
-                    .iter_mut()
-                    .map(async move |x| match x.logout().await {
-                        Ok(_) => None,
-                        Err(e) => Some(Error {
-                            operate: Operate::Logout,
-                            kind: Kind::Judge(e),
-                            handle: x.handle.clone(),
-                        }),
-                    }),
-            )
-            .await
-            .into_iter()
+    pub async fn logout(&self) -> Vec<Error> {
+        let sessions: Vec<_> = self
+            .session
+            .read()
+            .unwrap()
+            .iter()
             .flatten()
+            .cloned()
             .collect();
+        let ret: Vec<Error> = join_all(sessions.iter().map(|x| async move {
+            match x.logout().await {
+                Ok(_) => None,
+                Err(e) => Some(Error {
+                    operate: Operate::Logout,
+                    kind: Kind::Judge(e),
+                    handle: x.handle.clone(),
+                }),
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
         if ret.is_empty() {
-            self.session.clear();
-            self.list.clear();
+            self.session.write().unwrap().clear();
+            self.list.lock().unwrap().clear();
         }
         ret
     }
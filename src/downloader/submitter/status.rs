@@ -0,0 +1,111 @@
+extern crate tokio;
+
+use super::{audit::AuditError, budget::UsageError, Submitter};
+use crate::config::{
+    judge::submit::CHECK_DELAY,
+    submitter::{DELAY_PER_ACCOUNT, SUBMISSION_GET_DELAY},
+};
+use std::{cmp::max, mem::take, time::Duration};
+use tokio::time::Instant;
+
+pub struct AccountStatus {
+    pub handle: String,
+    pub online: bool,
+    pub submitted: usize,
+    pub success: usize,
+    pub failure: usize,
+    pub next_submit: Option<Duration>,
+    pub quarantined: Option<Duration>,
+}
+pub enum Event {
+    Quarantined(String, Duration),
+    Restored(String),
+    Usage(UsageError),
+    Audit(AuditError),
+}
+
+impl Submitter {
+    pub(super) fn push_event(&self, event: Event) {
+        self.event.lock().unwrap().push(event);
+    }
+    pub fn take_events(&self) -> Vec<Event> {
+        take(&mut *self.event.lock().unwrap())
+    }
+    pub(super) fn report_id(&self, id: usize, success: bool) -> bool {
+        let until = self.list.lock().unwrap().report(id, success);
+        match (until, self.get_session(id)) {
+            (Some(until), Some(s)) => {
+                self.push_event(Event::Quarantined(
+                    s.handle.clone(),
+                    until.saturating_duration_since(Instant::now()),
+                ));
+                true
+            }
+            (until, _) => until.is_some(),
+        }
+    }
+    pub fn submit_delay(&self) -> Duration {
+        self.list.lock().unwrap().submit_delay
+    }
+    pub fn online_count(&self) -> usize {
+        self.session_count()
+    }
+    pub fn estimate(&self, submissions: usize) -> Duration {
+        let accounts = self.session_count();
+        if submissions == 0 || accounts == 0 {
+            return Duration::from_secs(0);
+        }
+        let interval = max(self.submit_delay(), DELAY_PER_ACCOUNT / accounts as u32);
+        interval * (submissions - 1) as u32 + SUBMISSION_GET_DELAY + CHECK_DELAY
+    }
+    pub fn handles(&self) -> Vec<String> {
+        let mut ret: Vec<String> = self.account.lock().unwrap().keys().cloned().collect();
+        for s in self.session.read().unwrap().iter().flatten() {
+            if !ret.contains(&s.handle) {
+                ret.push(s.handle.clone());
+            }
+        }
+        ret.sort();
+        ret
+    }
+    pub fn status(&self) -> Vec<AccountStatus> {
+        let handles = self.handles();
+        let now = Instant::now();
+        let session = self.session.read().unwrap();
+        let list = self.list.lock().unwrap();
+        handles
+            .into_iter()
+            .map(|handle| {
+                match session
+                    .iter()
+                    .enumerate()
+                    .find(|(_, x)| matches!(x, Some(s) if s.handle == handle))
+                {
+                    Some((id, Some(s))) => {
+                        let state = list.state(id);
+                        AccountStatus {
+                            online: s.is_online(),
+                            submitted: state.submitted,
+                            success: state.success,
+                            failure: state.failure,
+                            next_submit: Some(state.next_submit.saturating_duration_since(now)),
+                            quarantined: state
+                                .quarantined
+                                .map(|x| x.saturating_duration_since(now)),
+                            handle,
+                        }
+                    }
+                    _ => AccountStatus {
+                        handle,
+                        online: false,
+                        submitted: 0,
+                        success: 0,
+                        failure: 0,
+                        next_submit: None,
+                        quarantined: None,
+                    },
+                }
+            })
+            .collect()
+    }
+}
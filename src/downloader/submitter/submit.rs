@@ -5,12 +5,22 @@ use super::{
     Submitter,
 };
 use crate::{
-    config::submitter::SUBMISSION_GET_DELAY,
-    judge::{problem::Problem, submit::Submission, Session},
+    cache::SubmitKey,
+    config::submitter::{LIMIT_RETRY, SUBMISSION_GET_DELAY},
+    judge::{
+        problem::Problem,
+        retry::ErrorClass,
+        submit::{Rejection, Submission},
+        Session,
+    },
+    submitter::{audit::Purpose, budget::Exceeded, status::Event},
+};
+use std::{
+    mem::{take, MaybeUninit},
+    sync::Arc,
 };
-use std::mem::{take, MaybeUninit};
 use tokio::{
-    task::{spawn_local, JoinHandle},
+    task::{spawn, JoinHandle},
     time::sleep,
 };
 
@@ -25,21 +35,6 @@ async fn get_last_submission<'a>(session: &'a Session, problem: &'a Problem) ->
             handle: session.handle.clone(),
         })
 }
-async fn submit<'a>(
-    session: &Session,
-    problem: &Problem,
-    language: &str,
-    code: &str,
-) -> Result<()> {
-    session
-        .submit(problem, language, code)
-        .await
-        .map_err(|x| Error {
-            operate: Operate::Submit,
-            kind: Kind::Judge(x),
-            handle: session.handle.clone(),
-        })
-}
 async fn get_result(
     handle: JoinHandle<Result<Submission>>,
     session: &Session,
@@ -55,53 +50,234 @@ async fn get_result(
 }
 
 impl Submitter {
+    async fn submit_code(
+        &self,
+        session: &Session,
+        problem: &Problem,
+        language: &str,
+        code: &str,
+        key: SubmitKey,
+        purpose: Purpose,
+    ) -> Result<Option<String>> {
+        self.reserve_budget(&session.handle, problem)
+            .map_err(|e| Error {
+                operate: Operate::Submit,
+                kind: Kind::Budget(e),
+                handle: session.handle.clone(),
+            })?;
+        let entry = self.draft(session, problem, language, code, key, purpose);
+        let mut result = session.submit(problem, language, code).await;
+        if matches!(&result, Err(e) if e.is_logged_out()) && self.recover(session).await {
+            result = session.submit(problem, language, code).await;
+        }
+        match &result {
+            Ok(_) => {
+                self.list.lock().unwrap().speed_up();
+                if let Err(e) = self.save_budget() {
+                    self.push_event(Event::Usage(e));
+                }
+            }
+            Err(e) => {
+                self.release_budget(&session.handle, problem);
+                if e.class() == ErrorClass::Throttled {
+                    self.list.lock().unwrap().slow_down();
+                }
+            }
+        }
+        match result {
+            Ok(_) => Ok(entry),
+            Err(x) => {
+                let e = Error {
+                    operate: Operate::Submit,
+                    kind: Kind::Judge(x),
+                    handle: session.handle.clone(),
+                };
+                self.audit_submit(entry, Err(&e));
+                Err(e)
+            }
+        }
+    }
+
     pub async fn submit(
-        &mut self,
+        &self,
         problem: &Problem,
         language: &str,
         code: &str,
+        key: SubmitKey,
+        purpose: Purpose,
     ) -> Result<Submission> {
-        let account = &self.session[self.list.get().await];
-        submit(account, problem, language, code).await?;
-        get_last_submission(account, problem).await
+        self.check_contest(problem).await.map_err(Error::refused)?;
+        let (id, account) = self.next_session().await?;
+        let ret = match self
+            .submit_code(&account, problem, language, code, key, purpose)
+            .await
+        {
+            Ok(entry) => {
+                let mut ret = get_last_submission(&account, problem).await;
+                if matches!(&ret, Err(e) if e.is_logged_out()) && self.recover(&account).await {
+                    ret = get_last_submission(&account, problem).await;
+                }
+                self.audit_submit(entry, ret.as_ref());
+                ret
+            }
+            Err(e) => Err(e),
+        };
+        if !matches!(&ret, Err(e) if e.rejection().is_some() || e.budget().is_some()) {
+            self.report_id(id, ret.is_ok());
+        }
+        ret
+    }
+
+    pub async fn attach(&self, submission: &mut Submission) -> Result<()> {
+        let handle = submission.handle().to_string();
+        match self.find_session(&handle) {
+            Some(session) => {
+                let mut result = session.attach(submission).await;
+                if matches!(&result, Err(e) if e.is_logged_out()) && self.recover(&session).await {
+                    result = session.attach(submission).await;
+                }
+                result.map_err(|e| Error {
+                    operate: Operate::Attach,
+                    kind: Kind::Judge(e),
+                    handle,
+                })
+            }
+            None => Err(Error {
+                operate: Operate::Attach,
+                kind: Kind::Offline,
+                handle,
+            }),
+        }
     }
 
-    pub async fn submit_iter<It: IntoIterator<Item = String>>(
-        &mut self,
+    async fn collect(
+        &self,
+        id: usize,
+        (handle, entry): (JoinHandle<Result<Submission>>, Option<String>),
+        session: &Session,
         problem: &Problem,
+    ) -> Result<Submission> {
+        let mut ret = get_result(handle, session).await;
+        if matches!(&ret, Err(e) if e.is_logged_out()) && self.recover(session).await {
+            ret = get_last_submission(session, problem).await;
+        }
+        self.audit_submit(entry, ret.as_ref());
+        self.report_id(id, ret.is_ok());
+        ret
+    }
+
+    pub async fn submit_iter<It: IntoIterator<Item = (SubmitKey, String)>>(
+        &self,
+        problem: &Arc<Problem>,
         language: &str,
         code: It,
     ) -> Vec<Result<Submission>> {
+        if let Err(r) = self.check_contest(problem).await {
+            return code
+                .into_iter()
+                .map(|_| Err(Error::refused(r.clone())))
+                .collect();
+        }
         let mut last = Vec::new();
         let mut result: Vec<Result<Submission>> = Vec::new();
-        last.resize_with(self.session.len(), || None);
-        for (index, code) in code.into_iter().enumerate() {
-            let id = self.list.get().await;
-            let account = &self.session[id];
-            if let Some((index, r)) = take(&mut last[id]) {
-                result[index] = get_result(r, account).await;
+        let mut abort = None;
+        let mut exhausted = None;
+        for (index, (key, code)) in code.into_iter().enumerate() {
+            if let Some(r) = abort {
+                result.push(Err(Error::aborted(r)));
+                continue;
+            }
+            if let Some(e) = exhausted {
+                result.push(Err(Error::budget_exceeded(e)));
+                continue;
             }
             #[allow(clippy::uninit_assumed_init)]
             result.push(unsafe { MaybeUninit::uninit().assume_init() });
-            match submit(account, problem, language, code.as_str()).await {
-                Ok(_) => {
-                    last[id] = {
-                        let account_ptr: *const Session = account;
-                        let problem_ptr: *const Problem = problem;
-                        Some((
+            let mut limit_retry = 0;
+            let mut error_retry = 0;
+            let mut skipped = 0;
+            loop {
+                let (id, account) = match self.next_session().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        exhausted = e.budget();
+                        result[index] = Err(e);
+                        break;
+                    }
+                };
+                if id >= last.len() {
+                    last.resize_with(id + 1, || None);
+                }
+                if let Some((index, r, _)) = take(&mut last[id]) {
+                    result[index] = self.collect(id, r, &account, problem).await;
+                }
+                match self
+                    .submit_code(
+                        &account,
+                        problem,
+                        language,
+                        code.as_str(),
+                        key,
+                        Purpose::Data,
+                    )
+                    .await
+                {
+                    Ok(entry) => {
+                        let problem = problem.clone();
+                        let session = account.clone();
+                        last[id] = Some((
                             index,
-                            spawn_local(unsafe {
-                                get_last_submission(&*account_ptr, &*problem_ptr)
-                            }),
-                        ))
+                            (
+                                spawn(async move { get_last_submission(&session, &problem).await }),
+                                entry,
+                            ),
+                            account,
+                        ));
+                        break;
                     }
+                    Err(e) if e.budget().is_some() => match e.budget() {
+                        Some(Exceeded::Account(_)) if skipped < self.session_count() => {
+                            skipped += 1
+                        }
+                        v => {
+                            exhausted = v;
+                            result[index] = Err(e);
+                            break;
+                        }
+                    },
+                    Err(e) => match e.rejection() {
+                        Some(Rejection::Limit) if limit_retry < LIMIT_RETRY => limit_retry += 1,
+                        Some(r) => {
+                            if r != Rejection::Limit && r != Rejection::Duplicate {
+                                abort = Some(r);
+                            }
+                            result[index] = Err(e);
+                            break;
+                        }
+                        None => {
+                            // Transient errors are retried with backoff on whichever
+                            // account is next, a quarantined account is skipped.
+                            let quarantined = self.report_id(id, false);
+                            let policy = self.retry_policy();
+                            match policy.delay(error_retry, e.class()) {
+                                Some(d) if error_retry + 1 < policy.count => {
+                                    error_retry += 1;
+                                    sleep(d).await;
+                                }
+                                _ if quarantined => (),
+                                _ => {
+                                    result[index] = Err(e);
+                                    break;
+                                }
+                            }
+                        }
+                    },
                 }
-                Err(e) => result[index] = Err(e),
             }
         }
         for (id, val) in last.into_iter().enumerate() {
-            if let Some((index, r)) = val {
-                result[index] = get_result(r, &self.session[id]).await;
+            if let Some((index, r, account)) = val {
+                result[index] = self.collect(id, r, &account, problem).await;
             }
         }
         result
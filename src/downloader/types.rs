@@ -1,5 +1,6 @@
 extern crate serde;
 
+use crate::encoding::{alphabet::Alphabet, compress::Codec};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -14,6 +15,14 @@ pub struct TestMeta {
     pub size: usize,
     pub output_size: usize,
     pub compress_size: usize,
+    #[serde(default)]
+    pub codec: Codec,
+    #[serde(default)]
+    pub alphabet: Alphabet,
+}
+impl TestMeta {
+    /// Input shown by the judge, if it is byte-exact.
+    pub fn exact_input(&self) -> Option<&str> {
+        self.input.as_deref().filter(|v| v.len() == self.size)
+    }
 }
-
-pub const BLOCK: usize = 500;
@@ -1,19 +1,30 @@
-#![feature(try_blocks)]
 #![feature(nll)]
 extern crate clap;
 extern crate pretty_env_logger;
 extern crate termcolor;
 extern crate tokio;
 
-use cf_downloader::{judge::Session, submitter::Submitter};
+use cf_downloader::{
+    encoding::{plugin::Plugin, scheme::Registry},
+    judge::{contest::ContestApi, retry::RetryPolicy, Session},
+    submitter::{guard::ContestGuard, Submitter},
+};
 use clap::{crate_description, crate_name, App, Arg};
 use pretty_env_logger::init_timed;
-use std::{fs::File, io::Write};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use termcolor::{Color, ColorChoice, StandardStream, WriteColor};
 
 #[macro_use]
 mod color;
 mod command {
+    pub mod account;
+    pub mod history;
+    pub mod job;
     pub mod problem;
     pub mod session;
 }
@@ -21,10 +32,13 @@ mod read;
 mod write;
 
 use command::{
+    account::{accounts, load_budget, write_events},
+    history::history,
+    job::{batch, resume},
     problem::problem_loop,
-    session::{login, logout, register},
+    session::{add_account, login, logout, register, save_sessions, SessionFile},
 };
-use read::{read_line, read_reader};
+use read::read_line;
 
 #[allow(unused_must_use)]
 #[tokio::main]
@@ -36,41 +50,175 @@ async fn main() {
         .version(get_version!("version"))
         .long_version(get_version!("long_version"))
         .arg(Arg::new("account").about("Path to account list"))
+        .arg(
+            Arg::new("budget")
+                .long("budget")
+                .takes_value(true)
+                .about("Path to submission budget"),
+        )
+        .arg(
+            Arg::new("audit")
+                .long("audit")
+                .takes_value(true)
+                .default_value("audit.jsonl")
+                .about("Path to submission audit log"),
+        )
+        .arg(
+            Arg::new("scheme")
+                .long("scheme")
+                .takes_value(true)
+                .about("Default encoding scheme"),
+        )
+        .arg(
+            Arg::new("plugin")
+                .long("plugin")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .about("Register an encoding plugin as NAME=PATH"),
+        )
+        .arg(
+            Arg::new("allow-live")
+                .long("allow-live")
+                .about("Allow submitting to contests that haven't finished"),
+        )
+        .arg(
+            Arg::new("retry-attempts")
+                .long("retry-attempts")
+                .takes_value(true)
+                .validator(|s| s.parse::<u32>())
+                .about("Attempts of each request to codeforces.com"),
+        )
+        .arg(
+            Arg::new("retry-delay")
+                .long("retry-delay")
+                .takes_value(true)
+                .validator(|s| s.parse::<u64>())
+                .about("Delay before the first retry in milliseconds, doubled after each one"),
+        )
+        .arg(
+            Arg::new("retry-max-delay")
+                .long("retry-max-delay")
+                .takes_value(true)
+                .validator(|s| s.parse::<u64>())
+                .about("Upper bound of the retry delay in milliseconds"),
+        )
         .get_matches();
-    let session = Session::new();
-    let mut submit = Submitter::new();
+    let mut retry = RetryPolicy::default();
+    if app.is_present("retry-attempts") {
+        retry.count = app.value_of_t_or_exit("retry-attempts");
+    }
+    if app.is_present("retry-delay") {
+        retry.delay = Duration::from_millis(app.value_of_t_or_exit("retry-delay"));
+    }
+    if app.is_present("retry-max-delay") {
+        retry.max_delay = Duration::from_millis(app.value_of_t_or_exit("retry-max-delay"));
+    }
+    let mut session = Session::new();
+    session.set_retry_policy(retry);
+    let submit = Arc::new(Submitter::new());
+    submit.set_retry_policy(retry);
+    submit.set_audit_log(app.value_of("audit").map(PathBuf::from));
+    let allow_live = app.is_present("allow-live");
+    if allow_live {
+        write_error!(&mut stdout, "Warning", "Live contest guard disabled");
+    }
+    let mut api = ContestApi::default();
+    api.set_retry_policy(retry);
+    submit.set_contest_guard(ContestGuard::new(api, allow_live));
+    let mut registry = Registry::default();
+    for p in app.values_of("plugin").into_iter().flatten() {
+        match p.split_once('=') {
+            Some((name, path)) => {
+                registry.register(Arc::new(Plugin::new(name.to_string(), PathBuf::from(path))))
+            }
+            None => {
+                write_error!(
+                    &mut stdout,
+                    "Error",
+                    "Invalid plugin {}, expect NAME=PATH",
+                    p
+                );
+            }
+        }
+    }
+    if let Some(s) = app.value_of("scheme") {
+        if let Err(e) = registry.set_default(s) {
+            write_error!(&mut stdout, "Error", "{}", e);
+        }
+    }
+    let registry = Arc::new(registry);
+    if let Some(f) = app.value_of("budget") {
+        load_budget(&mut stdout, &submit, Path::new(f));
+    }
+    let mut session_file: Option<SessionFile> = None;
     if let Some(f) = app.value_of("account") {
-        match File::open(f) {
-            Ok(v) => login(&mut stdout, &mut submit, v).await,
-            Err(e) => write_error!(&mut stdout, "Error", "Error open {}: {}", f, e),
+        if let Some(v) = login(&mut stdout, &submit, Path::new(f)).await {
+            session_file = Some(v);
         }
         stdout.reset();
     }
-    let stdout_ptr: *mut StandardStream = &mut stdout;
     loop {
         match read_line(&mut stdout, b"cf-downloader> ").trim() {
             "select" => {
                 if submit.is_empty() {
                     write_error!(&mut stdout, "Error", "No logined account!");
                 } else {
-                    problem_loop(&mut stdout, &session, &mut submit).await;
+                    problem_loop(&mut stdout, &session, &submit, &registry).await;
+                }
+            }
+            "batch" => {
+                if submit.is_empty() {
+                    write_error!(&mut stdout, "Error", "No logined account!");
+                } else {
+                    batch(&mut stdout, &submit, &registry).await;
+                }
+            }
+            "resume" => {
+                if submit.is_empty() {
+                    write_error!(&mut stdout, "Error", "No logined account!");
+                } else {
+                    resume(&mut stdout, &submit, &registry).await;
+                }
+            }
+            "history" => history(&mut stdout, &submit),
+            "schemes" => {
+                for name in registry.names() {
+                    if name == registry.default_scheme() {
+                        write_info!(&mut stdout, "Scheme", "{} (default)", name);
+                    } else {
+                        write_info!(&mut stdout, "Scheme", "{}", name);
+                    }
                 }
             }
             "exit" => break,
             "login" => {
-                login(
-                    &mut stdout,
-                    &mut submit,
-                    read_reader(unsafe { &mut *stdout_ptr }),
-                )
-                .await
+                let path = PathBuf::from(read_line(&mut stdout, b"File path: "));
+                if let Some(v) = login(&mut stdout, &submit, &path).await {
+                    session_file = Some(v);
+                }
+            }
+            "add_account" => add_account(&mut stdout),
+            "budget" => {
+                let path = PathBuf::from(read_line(&mut stdout, b"File path: "));
+                load_budget(&mut stdout, &submit, &path);
             }
             "register" => {
                 if let Some(v) = register(&mut stdout).await {
-                    submit.add_session(v);
+                    submit.add_session(v).await;
+                }
+            }
+            "logout" => {
+                logout(&mut stdout, &submit).await;
+                if let Some(p) = &session_file {
+                    save_sessions(&mut stdout, &submit, p);
+                }
+            }
+            cmd if cmd.starts_with("accounts ") => {
+                accounts(&mut stdout, &submit, cmd["accounts ".len()..].trim()).await;
+                if let Some(p) = &session_file {
+                    save_sessions(&mut stdout, &submit, p);
                 }
             }
-            "logout" => logout(&mut stdout, &mut submit).await,
             unknown => write_error!(
                 &mut stdout,
                 "Error",
@@ -78,8 +226,12 @@ async fn main() {
                 unknown
             ),
         }
+        write_events(&mut stdout, &submit);
         stdout.reset();
     }
-    logout(&mut stdout, &mut submit).await;
+    match &session_file {
+        Some(p) => save_sessions(&mut stdout, &submit, p),
+        None => logout(&mut stdout, &submit).await,
+    }
     stdout.reset();
 }
@@ -1,6 +1,8 @@
+extern crate libc;
 extern crate termcolor;
 
 use cf_downloader::{
+    account::vault::PASSPHRASE_ENV,
     encoding::Template,
     judge::{
         problem::{Problem, Type},
@@ -8,20 +10,28 @@ use cf_downloader::{
     },
 };
 use std::{
+    env,
     fs::File,
     io::{self, stdin, Read, Write},
+    path::Path,
 };
 use termcolor::{Color, StandardStream, WriteColor};
 
+/// Read a line and strip only its line ending.
 #[allow(unused_must_use)]
-pub fn read_line_to(stdout: &mut StandardStream, prompt: &[u8], dest: &mut String) {
-    dest.clear();
+fn read_raw_line_to(stdout: &mut StandardStream, prompt: &[u8], dest: &mut String) {
     loop {
+        dest.clear();
         stdout.write(prompt);
         stdout.flush();
         match stdin().read_line(dest) {
             Ok(_) => {
-                dest.truncate(dest.trim_end().len());
+                if dest.ends_with('\n') {
+                    dest.pop();
+                    if dest.ends_with('\r') {
+                        dest.pop();
+                    }
+                }
                 return;
             }
             Err(e) => write_error!(stdout, "Error", "Read: {}", e.to_string()),
@@ -29,11 +39,56 @@ pub fn read_line_to(stdout: &mut StandardStream, prompt: &[u8], dest: &mut Strin
         stdout.reset();
     }
 }
+pub fn read_line_to(stdout: &mut StandardStream, prompt: &[u8], dest: &mut String) {
+    read_raw_line_to(stdout, prompt, dest);
+    dest.truncate(dest.trim_end().len());
+}
 pub fn read_line(stdout: &mut StandardStream, prompt: &[u8]) -> String {
     let mut ret = String::new();
     read_line_to(stdout, prompt, &mut ret);
     ret
 }
+#[cfg(unix)]
+#[allow(unused_must_use)]
+pub fn read_password(stdout: &mut StandardStream, prompt: &[u8]) -> String {
+    let mut term = std::mem::MaybeUninit::<libc::termios>::uninit();
+    let old = match unsafe { libc::tcgetattr(libc::STDIN_FILENO, term.as_mut_ptr()) } {
+        0 => Some(unsafe { term.assume_init() }),
+        _ => None,
+    };
+    if let Some(old) = old {
+        let mut hidden = old;
+        hidden.c_lflag &= !libc::ECHO;
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &hidden) };
+    }
+    let mut ret = String::new();
+    read_raw_line_to(stdout, prompt, &mut ret);
+    if let Some(old) = old {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &old) };
+        stdout.write_all(b"\n");
+    }
+    ret
+}
+#[cfg(not(unix))]
+pub fn read_password(stdout: &mut StandardStream, prompt: &[u8]) -> String {
+    let mut ret = String::new();
+    read_raw_line_to(stdout, prompt, &mut ret);
+    ret
+}
+#[allow(unused_must_use)]
+pub fn read_passphrase(stdout: &mut StandardStream, confirm: bool) -> String {
+    if let Ok(v) = env::var(PASSPHRASE_ENV) {
+        return v;
+    }
+    loop {
+        let ret = read_password(stdout, b"Passphrase: ");
+        if !confirm || read_password(stdout, b"Confirm passphrase: ") == ret {
+            return ret;
+        }
+        write_error!(stdout, "Error", "Passphrase mismatch");
+        stdout.reset();
+    }
+}
 #[allow(unused_must_use)]
 pub fn read_usize(stdout: &mut StandardStream, prompt: &[u8], min: usize, max: usize) -> usize {
     let mut buf = String::new();
@@ -59,6 +114,12 @@ pub fn read_usize(stdout: &mut StandardStream, prompt: &[u8], min: usize, max: u
         stdout.reset();
     }
 }
+pub fn read_confirm(stdout: &mut StandardStream, prompt: &[u8]) -> bool {
+    matches!(
+        read_line(stdout, prompt).trim().to_lowercase().as_str(),
+        "y" | "yes"
+    )
+}
 #[allow(unused_must_use)]
 pub async fn read_problem(stdout: &mut StandardStream, session: &Session) -> Problem {
     let mut contest = String::new();
@@ -78,16 +139,10 @@ pub async fn read_problem(stdout: &mut StandardStream, session: &Session) -> Pro
 pub fn read_template(stdout: &mut StandardStream) -> Template {
     let lang = read_line(stdout, b"Language: ");
     let mut path = String::new();
-    let mut content = String::new();
     loop {
         read_line_to(stdout, b"File path: ", &mut path);
-        match File::open(&path).and_then(|mut f: File| f.read_to_string(&mut content)) {
-            Ok(_) => {
-                break Template {
-                    language: lang,
-                    content,
-                };
-            }
+        match Template::load(lang.clone(), Path::new(&path)) {
+            Ok(v) => break v,
             Err(e) => write_error!(stdout, "Error", "read file: {}", e.to_string()),
         }
         stdout.reset();
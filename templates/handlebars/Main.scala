@@ -0,0 +1,5 @@
+{{!-- Meta and data program, gzip compressed and base64 encoded. --}}
+{{#*inline "codec"}}gzip{{/inline}}
+{{#*inline "import"}}import java.util.zip.GZIPOutputStream;{{/inline}}
+{{#*inline "compress"}}new GZIPOutputStream(wdr){{/inline}}
+{{> scala}}
@@ -0,0 +1,27 @@
+{{!-- Meta and data program, gzip compressed and base-95 encoded. --}}
+{{#*inline "codec"}}gzip{{/inline}}
+{{#*inline "compress"}}new GZipStream(ms, CompressionLevel.Optimal){{/inline}}
+{{#*inline "alphabet"}}base95{{/inline}}
+{{#*inline "encode"}}
+    static string Encode(in byte[] data)
+    {
+      var ret = new StringBuilder((data.Length + 8) / 9 * 11);
+      var group = new char[11];
+      for (int i = 0; i < data.Length; i += 9)
+      {
+        BigInteger value = 0;
+        for (int j = 0; j < 9; ++j)
+        {
+          value = value * 256 + (i + j < data.Length ? data[i + j] : 0);
+        }
+        for (int j = 10; j >= 0; --j)
+        {
+          group[j] = (char)(' ' + (int)(value % 95));
+          value /= 95;
+        }
+        ret.Append(group);
+      }
+      return ret.ToString();
+    }
+{{/inline}}
+{{> csharp}}
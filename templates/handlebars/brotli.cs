@@ -0,0 +1,8 @@
+{{!-- Meta and data program, brotli compressed and base64 encoded. --}}
+{{#*inline "codec"}}brotli{{/inline}}
+{{#*inline "compress"}}new BrotliStream(ms, CompressionLevel.Optimal){{/inline}}
+{{#*inline "alphabet"}}base64{{/inline}}
+{{#*inline "encode"}}
+    static string Encode(in byte[] data) => Convert.ToBase64String(data);
+{{/inline}}
+{{> csharp}}
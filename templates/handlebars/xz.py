@@ -0,0 +1,5 @@
+{{!-- Meta and data program, xz compressed and base64 encoded. --}}
+{{#*inline "codec"}}xz{{/inline}}
+{{#*inline "import"}}import lzma{{/inline}}
+{{#*inline "compress"}}lzma.compress(raw, format=lzma.FORMAT_XZ){{/inline}}
+{{> python}}
@@ -0,0 +1,28 @@
+{{!-- Meta and data program, gzip compressed and Z85 encoded. --}}
+{{#*inline "codec"}}gzip{{/inline}}
+{{#*inline "compress"}}new GZipStream(ms, CompressionLevel.Optimal){{/inline}}
+{{#*inline "alphabet"}}z85{{/inline}}
+{{#*inline "encode"}}
+    const string Alphabet = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+    static string Encode(in byte[] data)
+    {
+      var ret = new StringBuilder((data.Length + 3) / 4 * 5);
+      var group = new char[5];
+      for (int i = 0; i < data.Length; i += 4)
+      {
+        ulong value = 0;
+        for (int j = 0; j < 4; ++j)
+        {
+          value = value * 256 + (i + j < data.Length ? data[i + j] : 0u);
+        }
+        for (int j = 4; j >= 0; --j)
+        {
+          group[j] = Alphabet[(int)(value % 85)];
+          value /= 85;
+        }
+        ret.Append(group);
+      }
+      return ret.ToString();
+    }
+{{/inline}}
+{{> csharp}}
@@ -0,0 +1,366 @@
+{{!-- Meta and data program, zstd compressed and base64 encoded. The JDK has
+no zstd, so the frame is built here from greedy LZ77 matches, Huffman coded
+literals and the predefined sequence tables. --}}
+{{#*inline "codec"}}zstd{{/inline}}
+{{#*inline "compress"}}
+  static final int BLOCK = 1 << 17, MIN_MATCH = 4, DEPTH = 32, HASH_LOG = 16;
+  static final int[] LL_NORM = {4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2,
+      2, 2, 2, 3, 2, 1, 1, 1, 1, 1, -1, -1, -1, -1};
+  static final int[] ML_NORM = {1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+      1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+      -1, -1};
+  static final int[] OF_NORM = {1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+      1, 1, -1, -1, -1, -1, -1};
+  static final int[] LL_BITS = {0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2,
+      3, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16};
+  static final int[] ML_BITS = {0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+      0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14,
+      15, 16};
+  static final int[] LL_BASE = bases(LL_BITS, 0), ML_BASE = bases(ML_BITS, 3);
+  static final Fse LL = new Fse(LL_NORM, 6), ML = new Fse(ML_NORM, 6), OF = new Fse(OF_NORM, 5);
+
+  static int[] bases(int[] bits, int first) {
+    int[] ret = new int[bits.length];
+    ret[0] = first;
+    for (int i = 1; i < bits.length; ++i) {
+      ret[i] = ret[i - 1] + (1 << bits[i - 1]);
+    }
+    return ret;
+  }
+  static int code(int[] base, int value) {
+    int ret = base.length - 1;
+    while (base[ret] > value) {
+      --ret;
+    }
+    return ret;
+  }
+  static int highBit(int value) {
+    return 31 - Integer.numberOfLeadingZeros(value);
+  }
+  static void writeLe(ByteArrayOutputStream out, long value, int bytes) {
+    for (int i = 0; i < bytes; ++i) {
+      out.write((int) (value >>> (8 * i)) & 0xff);
+    }
+  }
+
+  // Bit stream read backwards by the decoder, closed by a 1 bit.
+  static final class Bits {
+    final ByteArrayOutputStream out = new ByteArrayOutputStream();
+    long acc;
+    int count;
+
+    void add(long value, int bits) {
+      acc |= (value & ((1L << bits) - 1)) << count;
+      count += bits;
+      while (count >= 8) {
+        out.write((int) acc & 0xff);
+        acc >>>= 8;
+        count -= 8;
+      }
+    }
+    byte[] close() {
+      add(1, 1);
+      if (count > 0) {
+        out.write((int) acc);
+      }
+      return out.toByteArray();
+    }
+  }
+
+  // FSE encoding table for a normalized distribution.
+  static final class Fse {
+    final int log;
+    final int[] state, deltaBits, deltaFind;
+
+    Fse(int[] norm, int log) {
+      this.log = log;
+      int size = 1 << log, high = size - 1, n = norm.length;
+      int[] cumul = new int[n + 1], symbols = new int[size];
+      for (int s = 0; s < n; ++s) {
+        if (norm[s] == -1) {
+          cumul[s + 1] = cumul[s] + 1;
+          symbols[high--] = s;
+        } else {
+          cumul[s + 1] = cumul[s] + norm[s];
+        }
+      }
+      int step = (size >> 1) + (size >> 3) + 3, pos = 0;
+      for (int s = 0; s < n; ++s) {
+        for (int i = 0; i < norm[s]; ++i) {
+          symbols[pos] = s;
+          do {
+            pos = (pos + step) & (size - 1);
+          } while (pos > high);
+        }
+      }
+      state = new int[size];
+      for (int u = 0; u < size; ++u) {
+        state[cumul[symbols[u]]++] = size + u;
+      }
+      deltaBits = new int[n];
+      deltaFind = new int[n];
+      for (int s = 0, total = 0; s < n; ++s) {
+        int count = Math.max(norm[s], 1);
+        int bits = count == 1 ? log : log - highBit(count - 1);
+        deltaBits[s] = (bits << 16) - (count << bits);
+        deltaFind[s] = total - count;
+        total += count;
+      }
+    }
+  }
+  static final class State {
+    final Fse table;
+    int value;
+
+    State(Fse table, int symbol) {
+      this.table = table;
+      int bits = (table.deltaBits[symbol] + (1 << 15)) >> 16;
+      value = ((bits << 16) - table.deltaBits[symbol]) >> bits;
+      value = table.state[value + table.deltaFind[symbol]];
+    }
+    void encode(Bits out, int symbol) {
+      int bits = (value + table.deltaBits[symbol]) >> 16;
+      out.add(value, bits);
+      value = table.state[(value >> bits) + table.deltaFind[symbol]];
+    }
+    void flush(Bits out) {
+      out.add(value, table.log);
+    }
+  }
+
+  static void literalsHeader(ByteArrayOutputStream out, int type, int size) {
+    if (size < 32) {
+      out.write(type | size << 3);
+    } else if (size < 4096) {
+      writeLe(out, type | 1 << 2 | size << 4, 2);
+    } else {
+      writeLe(out, type | 3 << 2 | size << 4, 3);
+    }
+  }
+  // Code lengths of at most `limit` bits, flattening the counts until they fit.
+  static int[] huffmanLengths(int[] freq, int limit) {
+    int n = freq.length;
+    int[] counts = freq.clone(), parent = new int[2 * n], ret = new int[n];
+    while (true) {
+      PriorityQueue<Long> queue = new PriorityQueue<>();
+      for (int s = 0; s < n; ++s) {
+        if (counts[s] > 0) {
+          queue.add((long) counts[s] << 9 | s);
+        }
+      }
+      int next = n;
+      while (queue.size() > 1) {
+        long a = queue.poll(), b = queue.poll();
+        parent[(int) (a & 511)] = parent[(int) (b & 511)] = next;
+        queue.add(((a >>> 9) + (b >>> 9)) << 9 | next++);
+      }
+      int max = 0;
+      for (int s = 0; s < n; ++s) {
+        ret[s] = 0;
+        for (int x = s; counts[s] > 0 && x != next - 1; x = parent[x]) {
+          ++ret[s];
+        }
+        max = Math.max(max, ret[s]);
+      }
+      if (max <= limit) {
+        return ret;
+      }
+      for (int s = 0; s < n; ++s) {
+        counts[s] = (counts[s] + 1) / 2;
+      }
+    }
+  }
+  // Huffman coded literals with direct weights, or null if they don't fit.
+  static byte[] huffman(byte[] lit, int n) {
+    int last = 0;
+    int[] freq = new int[256];
+    for (int i = 0; i < n; ++i) {
+      last = Math.max(last, lit[i] & 0xff);
+      ++freq[lit[i] & 0xff];
+    }
+    if (last > 128) {
+      return null;
+    }
+    int[] len = huffmanLengths(Arrays.copyOf(freq, last + 1), 11), code = new int[last + 1];
+    int max = 0;
+    for (int l : len) {
+      max = Math.max(max, l);
+    }
+    for (int w = 1, slot = 0; w <= max; ++w) {
+      for (int s = 0; s <= last; ++s) {
+        if (len[s] != 0 && max + 1 - len[s] == w) {
+          code[s] = slot >> (w - 1);
+          slot += 1 << (w - 1);
+        }
+      }
+    }
+    ByteArrayOutputStream body = new ByteArrayOutputStream();
+    body.write(127 + last);
+    for (int s = 0; s < last; s += 2) {
+      int high = len[s] == 0 ? 0 : max + 1 - len[s];
+      int low = s + 1 == last || len[s + 1] == 0 ? 0 : max + 1 - len[s + 1];
+      body.write(high << 4 | low);
+    }
+    int streams = n <= 1023 ? 1 : 4, segment = (n + 3) / 4;
+    byte[][] encoded = new byte[streams][];
+    for (int k = 0; k < streams; ++k) {
+      int from = streams == 1 ? 0 : k * segment, to = streams == 1 ? n : Math.min(n, from + segment);
+      Bits bits = new Bits();
+      for (int i = to - 1; i >= from; --i) {
+        bits.add(code[lit[i] & 0xff], len[lit[i] & 0xff]);
+      }
+      encoded[k] = bits.close();
+    }
+    if (streams == 4) {
+      for (int k = 0; k < 3; ++k) {
+        writeLe(body, encoded[k].length, 2);
+      }
+    }
+    for (byte[] stream : encoded) {
+      body.writeBytes(stream);
+    }
+    int size = body.size();
+    ByteArrayOutputStream out = new ByteArrayOutputStream();
+    if (streams == 1) {
+      if (size > 1023) {
+        return null;
+      }
+      writeLe(out, 2 | (long) n << 4 | (long) size << 14, 3);
+    } else if (Math.max(n, size) < 16384) {
+      writeLe(out, 2 | 2 << 2 | (long) n << 4 | (long) size << 18, 4);
+    } else {
+      writeLe(out, 2 | 3 << 2 | (long) n << 4 | (long) size << 22, 5);
+    }
+    out.writeBytes(body.toByteArray());
+    return out.toByteArray();
+  }
+  static void literals(ByteArrayOutputStream out, byte[] lit, int n) {
+    boolean same = n > 1;
+    for (int i = 1; i < n && same; ++i) {
+      same = lit[i] == lit[0];
+    }
+    if (same) {
+      literalsHeader(out, 1, n);
+      out.write(lit[0]);
+      return;
+    }
+    byte[] compressed = n > 1 ? huffman(lit, n) : null;
+    if (compressed != null && compressed.length < n) {
+      out.writeBytes(compressed);
+    } else {
+      literalsHeader(out, 0, n);
+      out.write(lit, 0, n);
+    }
+  }
+  static void sequences(ByteArrayOutputStream out, int[] ll, int[] ml, int[] of, int count) {
+    if (count < 128) {
+      out.write(count);
+    } else if (count < 0x7f00) {
+      out.write((count >> 8) + 128);
+      out.write(count & 0xff);
+    } else {
+      out.write(0xff);
+      writeLe(out, count - 0x7f00, 2);
+    }
+    if (count == 0) {
+      return;
+    }
+    out.write(0);
+    int[] llc = new int[count], mlc = new int[count], ofc = new int[count];
+    for (int i = 0; i < count; ++i) {
+      llc[i] = code(LL_BASE, ll[i]);
+      mlc[i] = code(ML_BASE, ml[i]);
+      ofc[i] = highBit(of[i]);
+    }
+    Bits bits = new Bits();
+    int last = count - 1;
+    State mlState = new State(ML, mlc[last]), ofState = new State(OF, ofc[last]),
+        llState = new State(LL, llc[last]);
+    bits.add(ll[last] - LL_BASE[llc[last]], LL_BITS[llc[last]]);
+    bits.add(ml[last] - ML_BASE[mlc[last]], ML_BITS[mlc[last]]);
+    bits.add(of[last], ofc[last]);
+    for (int i = last - 1; i >= 0; --i) {
+      ofState.encode(bits, ofc[i]);
+      mlState.encode(bits, mlc[i]);
+      llState.encode(bits, llc[i]);
+      bits.add(ll[i] - LL_BASE[llc[i]], LL_BITS[llc[i]]);
+      bits.add(ml[i] - ML_BASE[mlc[i]], ML_BITS[mlc[i]]);
+      bits.add(of[i], ofc[i]);
+    }
+    mlState.flush(bits);
+    ofState.flush(bits);
+    llState.flush(bits);
+    out.writeBytes(bits.close());
+  }
+  static int hash(byte[] data, int i) {
+    int value = (data[i] & 0xff) | (data[i + 1] & 0xff) << 8 | (data[i + 2] & 0xff) << 16
+        | (data[i + 3] & 0xff) << 24;
+    return (value * 0x9e3779b1) >>> (32 - HASH_LOG);
+  }
+  static byte[] compress(byte[] data) {
+    int n = data.length;
+    ByteArrayOutputStream out = new ByteArrayOutputStream();
+    writeLe(out, 0xfd2fb528L, 4);
+    // Single segment with a 4 byte content size.
+    out.write(0xa0);
+    writeLe(out, n, 4);
+    if (n == 0) {
+      writeLe(out, 1, 3);
+    }
+    int[] head = new int[1 << HASH_LOG], prev = new int[n];
+    Arrays.fill(head, -1);
+    for (int start = 0; start < n; start += BLOCK) {
+      int end = Math.min(n, start + BLOCK), anchor = start, lit = 0, count = 0;
+      byte[] literals = new byte[end - start];
+      int[] ll = new int[(end - start) / MIN_MATCH + 1], ml = new int[ll.length],
+          of = new int[ll.length];
+      for (int i = start; i + MIN_MATCH <= end;) {
+        int h = hash(data, i), best = 0, offset = 0;
+        for (int c = head[h], depth = 0; c >= 0 && depth < DEPTH; c = prev[c], ++depth) {
+          int l = 0;
+          while (i + l < end && data[c + l] == data[i + l]) {
+            ++l;
+          }
+          if (l > best) {
+            best = l;
+            offset = i - c;
+          }
+        }
+        prev[i] = head[h];
+        head[h] = i;
+        // Far offsets cost more bits than a few literals.
+        if (best < MIN_MATCH || best * 4 < highBit(offset + 3) + 16) {
+          ++i;
+          continue;
+        }
+        System.arraycopy(data, anchor, literals, lit, i - anchor);
+        lit += i - anchor;
+        ll[count] = i - anchor;
+        ml[count] = best;
+        of[count++] = offset + 3;
+        for (int k = i + 1; k < i + best && k + MIN_MATCH <= n; ++k) {
+          h = hash(data, k);
+          prev[k] = head[h];
+          head[h] = k;
+        }
+        i += best;
+        anchor = i;
+      }
+      System.arraycopy(data, anchor, literals, lit, end - anchor);
+      lit += end - anchor;
+      ByteArrayOutputStream body = new ByteArrayOutputStream();
+      literals(body, literals, lit);
+      sequences(body, ll, ml, of, count);
+      int last = end == n ? 1 : 0;
+      if (body.size() < end - start) {
+        writeLe(out, last | 2 << 1 | body.size() << 3, 3);
+        out.writeBytes(body.toByteArray());
+      } else {
+        writeLe(out, last | (end - start) << 3, 3);
+        out.write(data, start, end - start);
+      }
+    }
+    return out.toByteArray();
+  }
+{{/inline}}
+{{> java}}
@@ -0,0 +1,19 @@
+use cf_downloader::{encoding::Template, judge::Verdict};
+use serde_json::json;
+use std::collections::HashMap;
+
+pub fn template(language: &str, content: &str) -> Template {
+    Template {
+        language: language.to_string(),
+        content: content.to_string(),
+        partials: HashMap::new(),
+    }
+}
+pub fn verdict(output: &str, answer: Option<&str>) -> Verdict {
+    serde_json::from_value(json!({
+        "input": null,
+        "output": output,
+        "answer": answer,
+    }))
+    .unwrap()
+}
@@ -0,0 +1,106 @@
+extern crate cf_downloader;
+extern crate tokio;
+
+use cf_downloader::{
+    judge::{
+        contest::{ContestApi, Phase},
+        problem::{Problem, Type},
+    },
+    submitter::guard::{ContestGuard, Refused},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+fn respond(request: &str) -> (&'static str, String) {
+    let contest = request
+        .split(['?', '&', ' '])
+        .find_map(|x| x.strip_prefix("contestId="))
+        .unwrap_or("");
+    let phase = match contest {
+        "1" => "FINISHED",
+        "2" => "CODING",
+        "100001" => "FINISHED",
+        _ => {
+            return (
+                "400 Bad Request",
+                format!(
+                    r#"{{"status":"FAILED","comment":"contestId: Contest with id {} not found"}}"#,
+                    contest
+                ),
+            )
+        }
+    };
+    (
+        "200 OK",
+        format!(
+            r#"{{"status":"OK","result":{{"contest":{{"id":{},"name":"Stand-in","type":"CF","phase":"{}","frozen":false,"durationSeconds":7200}},"problems":[],"rows":[]}}}}"#,
+            contest, phase
+        ),
+    )
+}
+
+async fn stand_in() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0; 1024];
+                while !buf.windows(4).any(|x| x == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&buf);
+                let (status, body) = respond(request.lines().next().unwrap_or(""));
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    format!("http://{}", addr)
+}
+
+fn problem(contest: &str) -> Problem {
+    Problem::new(Type::Contest, contest.to_string(), "A".to_string())
+}
+
+#[tokio::test]
+async fn reads_contest_phase() {
+    let api = ContestApi::new(stand_in().await);
+    assert_eq!(api.contest("1").await.unwrap().phase, Phase::Finished);
+    assert_eq!(api.contest("2").await.unwrap().phase, Phase::Coding);
+    assert!(api.contest("3").await.is_err());
+}
+
+#[tokio::test]
+async fn refuses_live_contest() {
+    let guard = ContestGuard::new(ContestApi::new(stand_in().await), false);
+    assert!(guard.check(&problem("1")).await.is_ok());
+    assert!(guard.check(&problem("100001")).await.is_ok());
+    assert!(matches!(
+        guard.check(&problem("2")).await,
+        Err(Refused::Live(_, Phase::Coding))
+    ));
+    assert!(matches!(
+        guard.check(&problem("3")).await,
+        Err(Refused::Check(_, _))
+    ));
+}
+
+#[tokio::test]
+async fn override_allows_live_contest() {
+    let guard = ContestGuard::new(ContestApi::new(stand_in().await), true);
+    assert!(guard.check(&problem("2")).await.is_ok());
+    assert!(guard.check(&problem("3")).await.is_ok());
+}
@@ -0,0 +1,168 @@
+extern crate cf_downloader;
+extern crate serde_json;
+
+mod common;
+
+use cf_downloader::encoding::{
+    handlebars::{escape, scheme::Handlebars},
+    scheme::Scheme,
+    Template,
+};
+use common::verdict;
+
+const ADVERSARIAL: &[&str] = &[
+    "",
+    "\"",
+    "\"\"\"",
+    "ends with \\",
+    "\\u000a\\u0022",
+    "\\\\u0041",
+    "line\nfeed\r\ncrlf\ttab",
+    "nul\0\x01\x1f\x7f",
+    ")\" )cf1\"",
+    "é € 😀 \u{2028}",
+    "{{ignore}} {{{raw}}}",
+    "$interp ${x} @\"verbatim\"",
+];
+
+fn template(content: &str) -> Template {
+    common::template("test", content)
+}
+/// Quoted literal with only printable ASCII and no unescaped quote inside.
+fn check_quoted(literal: &str) {
+    assert!(literal.len() >= 2 && literal.starts_with('"') && literal.ends_with('"'));
+    assert!(literal.chars().all(|c| (' '..='~').contains(&c)));
+    let mut escaped = false;
+    for c in literal[1..literal.len() - 1].chars() {
+        assert!(escaped || c != '"', "unescaped quote in {}", literal);
+        escaped = !escaped && c == '\\';
+    }
+    assert!(!escaped, "dangling backslash in {}", literal);
+}
+
+#[test]
+fn quoted_literals_are_safe() {
+    for i in ADVERSARIAL {
+        check_quoted(&escape::csharp(i));
+        check_quoted(&escape::java(i));
+        check_quoted(&escape::scala(i));
+        check_quoted(&escape::python(i));
+    }
+}
+
+#[test]
+fn escapes_per_language() {
+    let value = "\"\\\n\0\u{7f}é😀";
+    assert_eq!(
+        escape::csharp(value),
+        r#""\"\\\n\u0000\u007f\u00e9\ud83d\ude00""#
+    );
+    assert_eq!(escape::java(value), r#""\"\\\n\000\177\u00e9\ud83d\ude00""#);
+    assert_eq!(
+        escape::scala(value),
+        r#""\"\\\n\u0000\u007f\u00e9\ud83d\ude00""#
+    );
+    assert_eq!(escape::python(value), r#""\"\\\n\x00\x7f\xe9\U0001f600""#);
+    // Java translates \u escapes before lexing, so none may stand for a
+    // line break or quote.
+    assert_eq!(escape::java("\\u000a"), r#""\\u000a""#);
+    assert_eq!(escape::java("\u{0}001"), r#""\000001""#);
+}
+
+#[test]
+fn raw_string_avoids_delimiter() {
+    assert_eq!(escape::cpp_raw("plain"), r#"R"(plain)""#);
+    assert_eq!(escape::cpp_raw(")\""), r#"R"cf1()")cf1""#);
+    assert_eq!(escape::cpp_raw(")\" )cf1\""), r#"R"cf2()" )cf1")cf2""#);
+    for i in ADVERSARIAL {
+        let literal = escape::cpp_raw(i);
+        let open = literal.find('(').unwrap();
+        let close = format!("){}\"", &literal[2..open]);
+        assert_eq!(literal.find(&close), Some(literal.len() - close.len()));
+    }
+}
+
+#[test]
+fn byte_arrays() {
+    assert_eq!(escape::bytes("", false), "");
+    assert_eq!(escape::bytes("a\0é", false), "97, 0, 195, 169");
+    assert_eq!(escape::bytes("a\0é", true), "97, 0, -61, -87");
+}
+
+/// Helper, answer and the literal it renders.
+const RENDERED: &[(&str, &str, &str)] = &[
+    ("csharp_string", "", r#""""#),
+    ("java_string", "", r#""""#),
+    ("scala_string", "", r#""""#),
+    ("python_string", "", r#""""#),
+    ("cpp_raw_string", "", r#"R"()""#),
+    ("byte_array", "", ""),
+    ("csharp_string", "say \"hi\"\\", r#""say \"hi\"\\""#),
+    ("java_string", "say \"hi\"\\", r#""say \"hi\"\\""#),
+    ("scala_string", "say \"hi\"\\", r#""say \"hi\"\\""#),
+    ("python_string", "say \"hi\"\\", r#""say \"hi\"\\""#),
+    ("cpp_raw_string", "say \"hi\"\\", r#"R"(say "hi"\)""#),
+    ("csharp_string", "a\0b", r#""a\u0000b""#),
+    ("java_string", "a\0b", r#""a\000b""#),
+    ("scala_string", "a\0b", r#""a\u0000b""#),
+    ("python_string", "a\0b", r#""a\x00b""#),
+    ("byte_array", "a\0b", "97, 0, 98"),
+    ("csharp_string", "é😀", r#""\u00e9\ud83d\ude00""#),
+    ("java_string", "é😀", r#""\u00e9\ud83d\ude00""#),
+    ("scala_string", "é😀", r#""\u00e9\ud83d\ude00""#),
+    ("python_string", "é😀", r#""\xe9\U0001f600""#),
+    ("cpp_raw_string", "é😀", "R\"(é😀)\""),
+    ("byte_array", "é😀", "195, 169, 240, 159, 152, 128"),
+    ("csharp_string", ")\"", r#"")\"""#),
+    ("cpp_raw_string", ")\"", r#"R"cf1()")cf1""#),
+    ("cpp_raw_string", ")\" )cf1\"", r#"R"cf2()" )cf1")cf2""#),
+];
+
+fn render(content: &str, answer: &str) -> String {
+    let scheme = Handlebars;
+    let test = scheme
+        .decode_meta(verdict("1 4 1 hash=", Some(answer)))
+        .unwrap();
+
+    let mut meta = scheme.meta(&template(content), 1).unwrap();
+    meta.ignore(&test.data_id);
+    meta.init();
+    let ret = meta.generate().unwrap();
+
+    let mut encoder = scheme.encoder(&template(content), 1, 10).unwrap();
+    encoder.push_ignore(&test.data_id);
+    encoder.init();
+    assert_eq!(encoder.generate(0).unwrap(), ret);
+    ret
+}
+
+#[test]
+fn helpers_render_answers() {
+    for (helper, answer, expected) in RENDERED {
+        let content = format!(
+            "{{{{#each ignore}}}}{{{{{} this.answer}}}}{{{{/each}}}}",
+            helper
+        );
+        assert_eq!(
+            &render(&content, answer),
+            expected,
+            "{} {:?}",
+            helper,
+            answer
+        );
+    }
+    let signed = "{{#each ignore}}{{byte_array this.answer signed=true}}{{/each}}";
+    assert_eq!(
+        render(signed, "a\0é😀"),
+        "97, 0, -61, -87, -16, -97, -104, -128"
+    );
+}
+
+#[test]
+fn helpers_reject_non_strings() {
+    let mut meta = Handlebars
+        .meta(&template("{{csharp_string random}}"), 0)
+        .unwrap();
+    meta.init();
+    assert!(meta.generate().is_err());
+}
@@ -0,0 +1,143 @@
+extern crate cf_downloader;
+extern crate serde_json;
+extern crate tokio;
+
+mod common;
+
+use cf_downloader::encoding::{
+    plugin::Plugin,
+    scheme::{Registry, Scheme},
+    Template,
+};
+use common::verdict;
+use std::{path::PathBuf, sync::Arc};
+
+const INPUT: &[u8] = b"3\n1 2 3\n";
+const LENGTH: usize = 5;
+
+fn plugin() -> Plugin {
+    Plugin::new(
+        "reference".to_string(),
+        PathBuf::from(env!("CARGO_BIN_EXE_cf-plugin-reference")),
+    )
+}
+fn template() -> Template {
+    common::template("python3", "def solve():\n    print(0)\n")
+}
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|v| format!("{:02x}", v)).collect()
+}
+
+#[test]
+fn generates_meta_program() {
+    let scheme = plugin();
+    let ignored = scheme
+        .decode_meta(verdict("1\nignored=\n", Some("\"quoted\"\n")))
+        .unwrap();
+    let mut meta = scheme.meta(&template(), 1).unwrap();
+    meta.ignore(&ignored.data_id);
+    meta.init();
+    let source = meta.generate().unwrap();
+    assert!(source.contains("def solve():"));
+    assert!(source.contains(r#""ignored=": "\"quoted\"\n","#));
+    assert!(source.contains("print(len(raw))"));
+}
+
+#[test]
+fn decodes_meta() {
+    let test = plugin()
+        .decode_meta(verdict("8\nhash=\n", Some("6\n")))
+        .unwrap();
+    assert_eq!(test.data_id.hash, "hash=");
+    assert_eq!(test.size, INPUT.len());
+    assert_eq!(test.output_size, INPUT.len() * 2);
+}
+
+#[test]
+fn generates_data_program() {
+    let scheme = plugin();
+    let ignored = scheme.decode_meta(verdict("1\nignored=\n", None)).unwrap();
+    let mut encoder = scheme.encoder(&template(), 1, LENGTH).unwrap();
+    encoder.push_ignore(&ignored.data_id);
+    encoder.init();
+    let source = encoder.generate(LENGTH).unwrap();
+    assert!(source.contains(r#""ignored=": None,"#));
+    assert!(source.contains("raw.hex()[5:10]"));
+    encoder.pop_ignore();
+    assert!(!encoder.generate(0).unwrap().contains("ignored="));
+}
+
+#[test]
+fn round_trips_chunks() {
+    let scheme = plugin();
+    let test = scheme.decode_meta(verdict("8\nhash=\n", None)).unwrap();
+    let encoded = hex(INPUT);
+    let chunks: Vec<String> = encoded
+        .as_bytes()
+        .chunks(LENGTH)
+        .map(|v| format!("{}\r\n", String::from_utf8_lossy(v)))
+        .collect();
+
+    let mut decoder = scheme.decoder();
+    decoder.init(&test);
+    for i in chunks.iter() {
+        decoder.append_message(i);
+    }
+    assert_eq!(decoder.decode().unwrap(), INPUT);
+    decoder.clear();
+
+    let mut out = Vec::new();
+    {
+        let mut writer = scheme.writer(&test, Box::new(&mut out));
+        for i in chunks.iter() {
+            writer.append_message(i).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), INPUT.len());
+    }
+    assert_eq!(out, INPUT);
+}
+
+#[test]
+fn reports_errors() {
+    let scheme = plugin();
+    let test = scheme.decode_meta(verdict("8\nhash=\n", None)).unwrap();
+    let mut decoder = scheme.decoder();
+    decoder.init(&test);
+    decoder.append_message("zz");
+    assert_eq!(
+        decoder.decode().unwrap_err().to_string(),
+        "Plugin error: Invalid hex at 0"
+    );
+    decoder.clear();
+    decoder.append_message(&hex(&INPUT[..4]));
+    assert_eq!(
+        decoder.decode().unwrap_err().to_string(),
+        "Decoded size mismatch: expected 8, got 4"
+    );
+    assert!(scheme.decode_meta(verdict("", None)).is_err());
+
+    let missing = Plugin::new("missing".to_string(), PathBuf::from("/nonexistent/plugin"));
+    assert!(missing.calibration(&template(), 10).is_err());
+}
+
+#[test]
+fn registers_by_name() {
+    let mut registry = Registry::default();
+    registry.register(Arc::new(plugin()));
+    assert_eq!(registry.names(), vec!["handlebars", "reference"]);
+    let scheme = registry.get(Some("reference")).unwrap();
+    assert!(scheme
+        .calibration(&template(), 10)
+        .unwrap()
+        .contains("\"x\" * 10"));
+}
+
+#[tokio::test]
+async fn runs_on_single_threaded_runtime() {
+    assert!(plugin().calibration(&template(), 10).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn runs_on_multi_threaded_runtime() {
+    assert!(plugin().calibration(&template(), 10).is_ok());
+}
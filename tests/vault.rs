@@ -0,0 +1,86 @@
+extern crate cf_downloader;
+extern crate serde_yaml;
+
+use cf_downloader::account::{
+    vault::{self, Error},
+    Account,
+};
+use serde_yaml::Value;
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+fn sealed() -> Vec<u8> {
+    let list = vec![
+        Account {
+            handle: "tourist".to_string(),
+            password: "p@ss: \"word\"\n".to_string(),
+            proxy: None,
+        },
+        Account {
+            handle: "petr".to_string(),
+            password: "hunter2".to_string(),
+            proxy: Some("socks5://127.0.0.1:1080".to_string()),
+        },
+    ];
+    let mut ret = Vec::new();
+    vault::to_writer(&mut ret, &list, PASSPHRASE).unwrap();
+    ret
+}
+/// `sealed()` with `field` replaced by `value`.
+fn tampered(field: &str, value: Value) -> Vec<u8> {
+    let mut vault: Value = serde_yaml::from_slice(&sealed()).unwrap();
+    vault[field] = value;
+    serde_yaml::to_vec(&vault).unwrap()
+}
+
+#[test]
+fn round_trips() {
+    let content = sealed();
+    assert!(vault::is_vault(&content));
+    assert!(!String::from_utf8_lossy(&content).contains("hunter2"));
+    let list = vault::from_reader(content.as_slice(), PASSPHRASE).unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].handle, "tourist");
+    assert_eq!(list[0].password, "p@ss: \"word\"\n");
+    assert_eq!(list[0].proxy, None);
+    assert_eq!(list[1].handle, "petr");
+    assert_eq!(list[1].proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+}
+
+#[test]
+fn rejects_wrong_passphrase() {
+    assert!(matches!(
+        vault::from_reader(sealed().as_slice(), "wrong"),
+        Err(Error::Decrypt)
+    ));
+}
+
+#[test]
+fn rejects_tampered_tag() {
+    // A valid base64 tag of the right length, but not the one sealed.
+    let content = tampered("tag", Value::from("AAAAAAAAAAAAAAAAAAAAAA=="));
+    assert!(matches!(
+        vault::from_reader(content.as_slice(), PASSPHRASE),
+        Err(Error::Decrypt)
+    ));
+}
+
+#[test]
+fn rejects_unknown_version() {
+    let content = tampered("vault", Value::from(2));
+    assert!(matches!(
+        vault::from_reader(content.as_slice(), PASSPHRASE),
+        Err(Error::Version(2))
+    ));
+}
+
+#[test]
+fn rejects_iterations_out_of_range() {
+    for i in [0, 1, u32::MAX] {
+        let content = tampered("iterations", Value::from(i));
+        assert!(matches!(
+            vault::from_reader(content.as_slice(), PASSPHRASE),
+            Err(Error::Iterations(v)) if v == i
+        ));
+    }
+}